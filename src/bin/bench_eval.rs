@@ -0,0 +1,62 @@
+//! Times `evaluate5` called hand by hand against `evaluate5_batch` over
+//! every five card hand, and the same for seven cards on a sample.
+//!
+//!     cargo run --release --bin bench_eval
+
+extern crate poker_trainer;
+
+use std::time::Instant;
+
+use poker_trainer::evaluate::{evaluate5, evaluate5_batch, evaluate7, evaluate7_batch};
+
+fn report(name: &str, start: Instant, hands: usize, checksum: usize) {
+    let elapsed = start.elapsed();
+    let secs = elapsed.as_secs() as f64 + f64::from(elapsed.subsec_nanos()) * 1e-9;
+    println!("{:<16} {:>8.2} ns/hand  (checksum {})", name, secs * 1e9 / hands as f64, checksum);
+}
+
+fn main() {
+    let mut fives = Vec::new();
+    for a in 0..52u8 {
+        for b in 0..a {
+            for c in 0..b {
+                for d in 0..c {
+                    for e in 0..d {
+                        fives.push([a, b, c, d, e]);
+                    }
+                }
+            }
+        }
+    }
+
+    let start = Instant::now();
+    let mut sum = 0;
+    for h in &fives {
+        sum += evaluate5(h[0] as usize, h[1] as usize, h[2] as usize, h[3] as usize, h[4] as usize);
+    }
+    report("evaluate5", start, fives.len(), sum);
+
+    let mut ranks = vec![0; fives.len()];
+    let start = Instant::now();
+    evaluate5_batch(&fives, &mut ranks);
+    report("evaluate5_batch", start, fives.len(), ranks.iter().map(|&r| r as usize).sum());
+
+    // seven cards: each five card hand with two cards above it
+    let sevens: Vec<[u8; 7]> = fives.iter()
+        .filter(|h| h[0] < 50)
+        .map(|h| [h[0], h[1], h[2], h[3], h[4], 50, 51])
+        .collect();
+    // builds the seven card table outside the timing
+    evaluate7(0, 4, 8, 12, 20, 50, 51);
+    let start = Instant::now();
+    let mut sum = 0;
+    for h in &sevens {
+        sum += evaluate7(h[0] as usize, h[1] as usize, h[2] as usize, h[3] as usize, h[4] as usize, 50, 51);
+    }
+    report("evaluate7", start, sevens.len(), sum);
+
+    let mut ranks = vec![0; sevens.len()];
+    let start = Instant::now();
+    evaluate7_batch(&sevens, &mut ranks);
+    report("evaluate7_batch", start, sevens.len(), ranks.iter().map(|&r| r as usize).sum());
+}
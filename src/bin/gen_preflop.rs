@@ -0,0 +1,17 @@
+//! Prints the `EQUITY` table of `src/preflop.rs`.
+//!
+//!     cargo run --release --features parallel --bin gen_preflop > table.rs
+
+extern crate poker_trainer;
+
+use poker_trainer::preflop;
+
+fn main() {
+    let table = preflop::generate(|i| eprintln!("{} / 169", i + 1));
+    println!("static EQUITY: [u16; 28561] = [");
+    for row in table.chunks(8) {
+        let row: Vec<String> = row.iter().map(|x| format!("\t{},", x)).collect();
+        println!("{}", row.concat());
+    }
+    println!("];");
+}
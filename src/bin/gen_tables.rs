@@ -0,0 +1,34 @@
+//! Prints the lookup tables of `src/evaluate.rs` from `tables`.
+//!
+//!     cargo run --release --bin gen_tables > tables.rs
+
+extern crate poker_trainer;
+
+use poker_trainer::tables;
+
+fn print(name: &str, table: &[usize]) {
+    println!("const {}: [usize; {}] = [", name, table.len());
+    for row in table.chunks(8) {
+        let row: Vec<String> = row.iter().map(|x| format!("\t{},", x)).collect();
+        println!("{}", row.concat());
+    }
+    println!("];");
+    println!();
+}
+
+fn main() {
+    print("NOFLUSH5", &tables::noflush5());
+    println!("const DP: [[[usize;8];14];5] = [");
+    for n in tables::dp().iter() {
+        println!("\t[");
+        for len in n.iter() {
+            let row: Vec<String> = len.iter().map(|x| x.to_string()).collect();
+            println!("\t\t[{}],", row.join(",\t"));
+        }
+        println!("\t],");
+    }
+    println!("];");
+    println!();
+    print("FLUSH", &tables::flush());
+    print("SUITS", &tables::suits());
+}
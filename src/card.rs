@@ -1,5 +1,8 @@
 use rand::prelude::*;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::{Serialize, Serializer};
 use std::fmt::{self, Display};
+use std::str::FromStr;
 
 
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
@@ -36,6 +39,9 @@ pub struct Board (pub [Card; 5]);
 pub struct Flop (pub [Card; 3]);
 #[derive(PartialEq, Eq, Debug, Copy, Clone)]
 pub struct Hand (pub [Card; 2]);
+/// Four hole cards for Omaha, two of which must play
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct OmahaHand (pub [Card; 4]);
 
 impl Display for Flop {
     fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
@@ -57,6 +63,12 @@ impl Display for Hand {
     }
 }
 
+impl Display for OmahaHand {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        write!(fmt, "{}{}{}{}", self.0[0], self.0[1], self.0[2], self.0[3])
+    }
+}
+
 pub trait Evaluate {
     fn evaluate() -> (usize, String);
 }
@@ -80,7 +92,7 @@ impl Display for Card {
 
 impl Display for Suit {
     fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
-        use Suit::*;
+        use self::Suit::*;
         match self {
             Heart => write!(fmt, "♥"),
             Club => write!(fmt, "♣"),
@@ -92,7 +104,7 @@ impl Display for Suit {
 
 impl Display for Rank {
     fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
-        use Rank::*;
+        use self::Rank::*;
         match self {
             Ace => write!(fmt, "A"),
             Deuce => write!(fmt, "2"),
@@ -113,7 +125,7 @@ impl Display for Rank {
 
 impl Suit {
     pub fn get(i: u64) -> Suit {
-        use Suit::*;
+        use self::Suit::*;
         match i % 4 {
             0 => Club,
             1 => Diamond,
@@ -123,7 +135,7 @@ impl Suit {
         }
     }
     pub fn value(&self) -> u64 {
-        use Suit::*;
+        use self::Suit::*;
         match *self {
             Club    => 0,
             Diamond => 1,
@@ -131,11 +143,23 @@ impl Suit {
             Spade   => 3,
         }
     }
+    pub fn letter(&self) -> char {
+        use self::Suit::*;
+        match *self {
+            Club    => 'c',
+            Diamond => 'd',
+            Heart   => 'h',
+            Spade   => 's',
+        }
+    }
+    pub fn from_char(c: char) -> Option<Suit> {
+        "cdhs".find(c.to_ascii_lowercase()).map(|i| Suit::get(i as u64))
+    }
 }
 
 impl Rank {
     pub fn get(i: u64) -> Rank {
-        use Rank::*;
+        use self::Rank::*;
         match i % 13 {
             0  => Deuce,
             1  => Three,
@@ -155,7 +179,7 @@ impl Rank {
     }
 
     pub fn value(&self) -> u64 {
-        use Rank::*;
+        use self::Rank::*;
         match *self {
             Deuce =>    0,
             Three =>    1,
@@ -172,10 +196,23 @@ impl Rank {
             Ace =>      12,
         }
     }
+    pub fn from_char(c: char) -> Option<Rank> {
+        "23456789TJQKA".find(c.to_ascii_uppercase()).map(|i| Rank::get(i as u64))
+    }
 }
 impl Card {
+    /// Inverse of `CardRepr::to_int`
+    pub fn from_int(id: usize) -> Card {
+        Card(Suit::get(id as u64 % 4), Rank::get(id as u64 / 4))
+    }
+
+    /// Short text notation, e.g. "As"
+    pub fn to_text(&self) -> String {
+        format!("{}{}", self.1, self.0.letter())
+    }
+
     pub fn to_html(&self) -> String {
-        use Suit::*;
+        use self::Suit::*;
         let suit = match self.0 {
             Spade => "spades",
             Club => "clubs",
@@ -196,6 +233,127 @@ impl Card {
     }
 }
 
+impl FromStr for Card {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.trim().chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(r), Some(u), None) => match (Rank::from_char(r), Suit::from_char(u)) {
+                (Some(rank), Some(suit)) => Ok(Card(suit, rank)),
+                _ => Err(format!("bad card: {}", s)),
+            },
+            _ => Err(format!("bad card: {}", s)),
+        }
+    }
+}
+
+/// Reads cards written back to back or separated by spaces, e.g. "AsKd 7h"
+pub fn parse_cards(s: &str) -> Result<Vec<Card>, String> {
+    let chars: Vec<char> = s.chars().filter(|c| !c.is_whitespace() && *c != ',').collect();
+    chars.chunks(2)
+        .map(|c| c.iter().collect::<String>().parse())
+        .collect()
+}
+
+impl FromStr for Hand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(s)?;
+        if cards.len() != 2 || cards[0] == cards[1] {
+            return Err(format!("bad hand: {}", s));
+        }
+        Ok(Hand([cards[0], cards[1]]))
+    }
+}
+
+impl FromStr for OmahaHand {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(s)?;
+        let distinct = cards.iter().enumerate().all(|(i, c)| !cards[..i].contains(c));
+        if cards.len() != 4 || !distinct {
+            return Err(format!("bad omaha hand: {}", s));
+        }
+        Ok(OmahaHand([cards[0], cards[1], cards[2], cards[3]]))
+    }
+}
+
+impl FromStr for Flop {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let cards = parse_cards(s)?;
+        let distinct = cards.iter().enumerate().all(|(i, c)| !cards[..i].contains(c));
+        if cards.len() != 3 || !distinct {
+            return Err(format!("bad flop: {}", s));
+        }
+        Ok(Flop([cards[0], cards[1], cards[2]]))
+    }
+}
+
+/// Cards in text notation back to back, e.g. "AhKh"
+pub fn cards_to_text(cards: &[Card]) -> String {
+    cards.iter().map(|c| c.to_text()).collect()
+}
+
+impl Hand {
+    pub fn to_text(&self) -> String {
+        cards_to_text(&self.0)
+    }
+}
+
+impl Flop {
+    /// In dealt order, unlike `Display`
+    pub fn to_text(&self) -> String {
+        cards_to_text(&self.0)
+    }
+}
+
+/// Serializes as the text notation
+macro_rules! serde_as_text {
+    ($($t:ty),*) => {$(
+        impl Serialize for $t {
+            fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+                s.serialize_str(&self.to_text())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $t {
+            fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+                String::deserialize(d)?.parse().map_err(de::Error::custom)
+            }
+        }
+    )*};
+}
+
+serde_as_text!(Card, Hand, Flop);
+
+/// The `to_52` bit of the card with `to_int` id `id`
+pub fn id_to_52(id: usize) -> u64 {
+    let (rank, suit) = (id / 4, id % 4);
+    1 << ((3 - suit) * 13 + (12 - rank))
+}
+
+/// The `to_int` id of the card at bit `bit` of a `to_52` mask
+pub fn bit_to_id(bit: u32) -> usize {
+    let (suit, rank) = (3 - bit as usize / 13, 12 - bit as usize % 13);
+    rank * 4 + suit
+}
+
+/// Card ids in a `to_52` mask, lowest id first
+pub fn mask_to_ids(mask: u64) -> Vec<usize> {
+    let mut ret: Vec<usize> = (0..52).filter(|b| mask & 1 << b != 0).map(bit_to_id).collect();
+    ret.sort();
+    ret
+}
+
+pub fn ids_to_mask(ids: &[usize]) -> u64 {
+    ids.iter().fold(0, |m, &id| m | id_to_52(id))
+}
+
 impl CardRepr for Card {
     fn to_int(&self) -> usize {
         (self.1.value() * 4 + self.0.value()) as usize
@@ -271,12 +429,27 @@ mod tests {
         assert_eq!(0b1000000000000000000000000000000000000000000000000000, Card(Club, Deuce).to_52());
     }
 
+    #[test]
+    fn test_id_schemes() {
+        use super::*;
+
+        for id in 0..52 {
+            let card = Card::from_int(id);
+            assert_eq!(card.to_52(), id_to_52(id));
+            assert_eq!(id, bit_to_id(id_to_52(id).trailing_zeros()));
+        }
+        let ids = vec![0, 7, 25, 48, 51];
+        let mask = ids_to_mask(&ids);
+        assert_eq!(5, mask.count_ones());
+        assert_eq!(ids, mask_to_ids(mask));
+    }
+
     #[test]
     fn test_evals() {
-        use Suit::*;
-        use Rank::*;
+        use card::Suit::*;
+        use card::Rank::*;
         use card::CardRepr;
-        use Card;
+        use card::Card;
         use evaluate::evaluate5;
 
         let a = Card(Club, Ace);
@@ -308,5 +481,123 @@ mod tests {
         );
 
         assert_eq!(1600, result);
+
+        // flushes in every suit, not only clubs
+        for &suit in &[Club, Diamond, Heart, Spade] {
+            let ids: Vec<usize> = [Ace, King, Queen, Jack, Nine].iter()
+                .map(|&r| Card(suit, r).to_int())
+                .collect();
+            assert_eq!(323, evaluate5(ids[0], ids[1], ids[2], ids[3], ids[4]));
+        }
+    }
+
+    #[test]
+    fn test_evaluate7() {
+        use card::parse_cards;
+        use card::CardRepr;
+        use evaluate::{evaluate5, evaluate7};
+
+        let best = |s: &str| {
+            let ids: Vec<usize> = parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect();
+            evaluate7(ids[0], ids[1], ids[2], ids[3], ids[4], ids[5], ids[6])
+        };
+        // royal flush among seven
+        assert_eq!(1, best("AhKhQhJhTh2c3d"));
+        // two pair plays the best kicker
+        let ids: Vec<usize> = parse_cards("AsAdKsKdQc").unwrap().iter().map(|c| c.to_int()).collect();
+        assert_eq!(evaluate5(ids[0], ids[1], ids[2], ids[3], ids[4]), best("AsAdKsKd2c3hQc"));
+        // wheel with a pair on board
+        assert_eq!(1609, best("Ah2c3d4s5h5c9d"));
+    }
+
+    #[test]
+    fn test_evaluate_mask() {
+        use card::{parse_cards, CardRepr};
+        use evaluate::{evaluate5, evaluate7, evaluate_mask};
+
+        let mask = |s: &str| parse_cards(s).unwrap().iter().fold(0, |m, c| m | c.to_52());
+        assert_eq!(1, evaluate_mask(mask("AhKhQhJhTh")));
+        assert_eq!(1609, evaluate_mask(mask("Ah2c3d4s5h5c9d")));
+        for s in &["AsAdKsKdQc", "7c5d4h3s2c", "9s8s6s3s2s"] {
+            let ids: Vec<usize> = parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect();
+            assert_eq!(evaluate5(ids[0], ids[1], ids[2], ids[3], ids[4]), evaluate_mask(mask(s)));
+        }
+        let ids: Vec<usize> = parse_cards("Kd7c7h2s9d3d5d").unwrap().iter().map(|c| c.to_int()).collect();
+        assert_eq!(evaluate7(ids[0], ids[1], ids[2], ids[3], ids[4], ids[5], ids[6]),
+            evaluate_mask(mask("Kd7c7h2s9d3d5d")));
+    }
+
+    #[test]
+    fn test_short_deck() {
+        use card::parse_cards;
+        use card::CardRepr;
+        use evaluate::{evaluate_short_deck, evaluate_short_deck7};
+
+        let ids = |s: &str| parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect::<Vec<_>>();
+        let rank = |s: &str| {
+            let i = ids(s);
+            evaluate_short_deck(i[0], i[1], i[2], i[3], i[4])
+        };
+        assert_eq!(1609, rank("Ac6d7h8s9c"));
+        assert_eq!(10, rank("Ac6c7c8c9c"));
+        assert!(rank("6c7d8h9sTc") < rank("Ac6d7h8s9c"));
+        // flushes beat full houses
+        assert!(rank("Ac9c8c7c6c") < rank("AdAhAsKcKd"));
+        assert!(rank("AdAhAsKcKd") < rank("AcKdQhJsTc"));
+        assert!(rank("Kc9c8c7c6c") < rank("6d6h6s7c7d"));
+        let i = ids("Ac6d7h8s9cKdKh");
+        assert_eq!(1609, evaluate_short_deck7([i[0], i[1], i[2], i[3], i[4], i[5], i[6]]));
+    }
+
+    #[test]
+    fn test_ace_five() {
+        use card::parse_cards;
+        use card::CardRepr;
+        use evaluate::{evaluate_ace_five, evaluate_low8, evaluate_low8_7, LOW8};
+
+        let ids = |s: &str| parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect::<Vec<_>>();
+        let low = |s: &str| {
+            let i = ids(s);
+            evaluate_ace_five(i[0], i[1], i[2], i[3], i[4])
+        };
+        // the wheel is the nuts even when suited
+        assert_eq!(1, low("Ac2c3c4c5c"));
+        assert_eq!(2, low("Ad2c3c4c6h"));
+        assert_eq!(LOW8, low("8c7d6h5s4c"));
+        assert_eq!(LOW8 + 1, low("9c4d3h2sAc"));
+        // a pair loses to any five different cards
+        assert!(low("AcAd2c3c4c") > low("KcQdJhTs9c"));
+        assert_eq!(6175, low("KcKdKhKsQc"));
+        let i = ids("9c4d3h2sAc");
+        assert_eq!(None, evaluate_low8(i[0], i[1], i[2], i[3], i[4]));
+        let i = ids("AcKd3h2s9c5d4h");
+        assert_eq!(Some(1), evaluate_low8_7([i[0], i[1], i[2], i[3], i[4], i[5], i[6]]));
+    }
+
+    #[test]
+    fn test_deuce_seven() {
+        use card::parse_cards;
+        use card::CardRepr;
+        use evaluate::{evaluate_ace_five, evaluate_deuce_seven};
+
+        let ids = |s: &str| parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect::<Vec<_>>();
+        let low = |s: &str| {
+            let i = ids(s);
+            (evaluate_deuce_seven(i[0], i[1], i[2], i[3], i[4]), evaluate_ace_five(i[0], i[1], i[2], i[3], i[4]))
+        };
+        assert_eq!(1, low("7c5d4h3s2c").0);
+        assert_eq!(2, low("7c6d4h3s2c").0);
+        // the ace plays high in deuce-to-seven, so A5432 is no straight but
+        // the lowest ace high: behind any king high, ahead of any pair
+        assert!(low("Ac2d3h4s5c").0 > low("Kc8d4h3s2c").0);
+        assert!(low("Ac2d3h4s5c").0 < low("Ac6d4h3s2c").0);
+        assert!(low("Ac2d3h4s5c").0 < low("2c2d5h4s3c").0);
+        // suited it is the lowest ace high flush, still behind a straight
+        assert!(low("Ac2c3c4c5c").0 < low("Ac6c4c3c2c").0);
+        assert!(low("Ac2c3c4c5c").0 > low("6c7d8h9sTc").0);
+        assert_eq!(1, low("Ac2d3h4s5c").1);
+        // a flush counts against the hand
+        assert!(low("7c5c4c3c2c").0 > low("AcAdKhKsQc").0);
+        assert_eq!(7462, low("AsKsQsJsTs").0);
     }
 }
\ No newline at end of file
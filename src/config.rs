@@ -0,0 +1,216 @@
+use rand::prelude::*;
+use serde_json;
+use toml;
+
+use card::Rank;
+use rake::Rake;
+use texture;
+
+/// A value drawn with probability proportional to `weight`
+#[derive(Serialize, Deserialize, PartialEq, Debug, Copy, Clone)]
+pub struct Weighted {
+    pub value: f32,
+    #[serde(default = "default_weight")]
+    pub weight: f32,
+}
+
+fn default_weight() -> f32 {
+    1.
+}
+
+/// False for zero, negatives and NaN
+fn positive(x: f32) -> bool {
+    x > 0.
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Distribution(pub Vec<Weighted>);
+
+impl Distribution {
+    pub fn uniform(values: &[f32]) -> Self {
+        Distribution(values.iter().map(|&value| Weighted { value, weight: 1. }).collect())
+    }
+
+    pub fn sample(&self) -> f32 {
+        let total: f32 = self.0.iter().map(|w| w.weight).sum();
+        let mut r = random::<f32>() * total;
+        for w in &self.0 {
+            if r < w.weight {
+                return w.value;
+            }
+            r -= w.weight;
+        }
+        self.0[self.0.len() - 1].value
+    }
+
+    fn validate(&self, name: &str) -> Result<(), String> {
+        if self.0.is_empty() {
+            return Err(format!("{} is empty", name));
+        }
+        if self.0.iter().any(|w| !positive(w.value) || w.weight < 0. || w.weight.is_nan()) {
+            return Err(format!("{} has a non-positive value or negative weight", name));
+        }
+        if !positive(self.0.iter().map(|w| w.weight).sum()) {
+            return Err(format!("{} has no weight", name));
+        }
+        Ok(())
+    }
+}
+
+/// Stakes and sizings the drills are dealt from.
+///
+/// Pot sizes are in big blinds and bets are fractions of the pot.
+/// Every field is optional in the file and falls back to the default.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+#[serde(default)]
+pub struct Config {
+    pub currency: String,
+    pub small_blind: f32,
+    pub big_blind: f32,
+    pub pot_sizes: Distribution,
+    pub bet_ratios: Distribution,
+    pub rake: Rake,
+    /// Only deal flops with all of these textures, e.g. ["monotone"] or
+    /// ["rainbow", "dry"]
+    pub flop_textures: Vec<String>,
+    /// Deal from the 36 card deck without deuces through fives
+    pub short_deck: bool,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            currency: "$".to_owned(),
+            small_blind: 5.,
+            big_blind: 10.,
+            pot_sizes: Distribution::uniform(&[10., 15., 20., 30., 40., 50., 80., 100., 120., 200.]),
+            bet_ratios: Distribution::uniform(&[0.08, 0.1, 0.15, 0.175, 0.18, 0.2, 0.5, 0.75, 0.8, 1.]),
+            rake: Rake::new(0.05, 10., true),
+            flop_textures: Vec::new(),
+            short_deck: false,
+        }
+    }
+}
+
+impl Config {
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let config: Config = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn from_toml(s: &str) -> Result<Self, String> {
+        let config: Config = toml::from_str(s).map_err(|e| e.to_string())?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    /// Parses either format, JSON being anything that starts with `{`
+    pub fn parse(s: &str) -> Result<Self, String> {
+        if s.trim_start().starts_with('{') {
+            Config::from_json(s)
+        } else {
+            Config::from_toml(s)
+        }
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        self.pot_sizes.validate("pot_sizes")?;
+        self.bet_ratios.validate("bet_ratios")?;
+        if !positive(self.small_blind) || !positive(self.big_blind) || self.big_blind < self.small_blind {
+            return Err("blinds must be positive with big_blind >= small_blind".to_owned());
+        }
+        if !(0. ..=1.).contains(&self.rake.percent) {
+            return Err("rake percent must be between 0 and 1".to_owned());
+        }
+        if self.rake.cap < 0. || self.rake.cap.is_nan() {
+            return Err("rake cap must not be negative".to_owned());
+        }
+        if let Some(label) = self.flop_textures.iter().find(|l| !texture::is_label(l)) {
+            return Err(format!("unknown flop texture \"{}\"", label));
+        }
+        if !texture::any_flop(&self.flop_textures, self.lowest_rank(), |_| true) {
+            return Err(format!("no flop is {}", self.flop_textures.join(" and ")));
+        }
+        Ok(())
+    }
+
+    pub fn rand_pot_size(&self) -> f32 {
+        self.pot_sizes.sample() * self.big_blind
+    }
+
+    pub fn rand_bet_ratio(&self) -> f32 {
+        self.bet_ratios.sample()
+    }
+
+    /// Lowest rank in the deck
+    pub fn lowest_rank(&self) -> Rank {
+        if self.short_deck { Rank::Six } else { Rank::Deuce }
+    }
+
+    pub fn deck_size(&self) -> u8 {
+        (13 - self.lowest_rank().value() as u8) * 4
+    }
+
+    /// Formats an amount in the configured currency
+    pub fn money(&self, amount: f32) -> String {
+        format!("{}{:.2}", self.currency, amount)
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_parse_config() {
+        use super::{Config, Weighted};
+
+        let toml = "
+            currency = \"€\"
+            big_blind = 2.0
+            small_blind = 1.0
+            pot_sizes = [{ value = 10.0, weight = 3.0 }, { value = 20.0 }]
+            bet_ratios = [{ value = 0.5 }]
+            [rake]
+            percent = 0.1
+            cap = 4.0
+            no_flop_no_drop = false
+        ";
+        let config = Config::parse(toml).unwrap();
+        assert_eq!("€", config.currency);
+        assert_eq!(Weighted { value: 20., weight: 1. }, config.pot_sizes.0[1]);
+        assert_eq!(4., config.rake.cap);
+        assert_eq!(1., config.rand_bet_ratio() * 2.);
+
+        let json = r#"{ "big_blind": 4, "small_blind": 2, "bet_ratios": [{ "value": 0.75 }] }"#;
+        let config = Config::parse(json).unwrap();
+        assert_eq!(Config::default().pot_sizes, config.pot_sizes);
+        assert_eq!(0.75, config.rand_bet_ratio());
+
+        assert!(Config::parse(r#"{ "bet_ratios": [] }"#).is_err());
+        assert!(Config::parse("bet_ratios = [{ value = 0.5, weight = -1.0 }]").is_err());
+    }
+
+    #[test]
+    fn test_validate_rake() {
+        use super::Config;
+
+        let rake = |percent: &str, cap: &str| {
+            Config::parse(&format!("[rake]\npercent = {}\ncap = {}\nno_flop_no_drop = true", percent, cap))
+        };
+        assert!(rake("0.0", "0.0").is_ok());
+        assert!(rake("1.0", "5.0").is_ok());
+        assert!(rake("5.0", "3.0").is_err());
+        assert!(rake("-0.05", "3.0").is_err());
+        assert!(rake("0.05", "-1.0").is_err());
+        assert!(rake("nan", "3.0").is_err());
+    }
+
+    #[test]
+    fn test_validate_textures() {
+        use super::Config;
+
+        assert!(Config::parse("flop_textures = [\"Two-Tone\", \"wet\"]").is_ok());
+        assert!(Config::parse("flop_textures = [\"suited\"]").is_err());
+        assert!(Config::parse("flop_textures = [\"rainbow\", \"monotone\"]").is_err());
+        assert!(Config::parse("flop_textures = [\"5 high\"]\nshort_deck = true").is_err());
+    }
+}
@@ -0,0 +1,204 @@
+//! The deck scenarios are dealt from, full or short deck, and the draws
+//! the flop scenarios are built on.
+
+use rand::prelude::*;
+
+use card::{Card, Flop, Hand, Rank, Suit};
+use config::Config;
+
+pub struct Deck {
+    deck: Vec<Card>,
+    /// Value of the lowest rank dealt
+    lowest: u64,
+}
+
+impl Deck {
+    pub fn new() -> Self {
+        Deck::from_rank(Rank::Deuce)
+    }
+
+    /// Four suits of every rank from `lowest` up to aces, e.g. sixes for
+    /// short deck
+    pub fn from_rank(lowest: Rank) -> Self {
+        let mut deck = Vec::new();
+        for i in lowest.value()..13 {
+            for j in 0..4 {
+                let suit = Suit::get(j);
+                let num = Rank::get(i);
+                deck.push(Card(suit, num));
+            }
+        }
+
+        Self {
+            deck,
+            lowest: lowest.value(),
+        }
+    }
+
+    pub fn for_config(config: &Config) -> Self {
+        Deck::from_rank(config.lowest_rank())
+    }
+
+    /// The deck for `config` with `cards` taken out. Fails on a card dealt
+    /// twice or missing from the deck, like a five in short deck.
+    pub fn without(config: &Config, cards: &[Card]) -> Result<Self, String> {
+        let mut deck = Deck::for_config(config);
+        for card in cards {
+            if !deck.remove_card(card) {
+                return Err(format!("{} is dealt twice or not in the deck", card.to_text()));
+            }
+        }
+        Ok(deck)
+    }
+
+    pub fn remove_card(&mut self, card: &Card) -> bool {
+        match self.deck.iter().position(|c| c == card) {
+            Some(i) => {
+                self.deck.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// The cards left, in no particular order
+    pub fn cards(&self) -> &[Card] {
+        &self.deck
+    }
+
+    pub fn _deal_one_random(&mut self) -> Option<Card> {
+        let i : usize = random::<usize>() % self.deck.len() as usize;
+        Some(self.deck[i])
+    }
+
+    pub fn deal_one_rand(&mut self) -> Option<Card> {
+        let card = self._deal_one_random()?;
+        self.remove_card(&card);
+        Some(card)
+    }
+
+    pub fn deal_suit(&mut self, suit: Suit) -> Option<Card> {
+        let mut card = self._deal_one_random();
+        while card?.0 != suit {
+            card = self._deal_one_random();
+        }
+        self.remove_card(&card?);
+        card
+    }
+
+    pub fn deal_rank(&mut self, rank: Rank) -> Option<Card> {
+        let mut card = self._deal_one_random();
+        while card?.1 != rank {
+            card = self._deal_one_random();
+        }
+        self.remove_card(&card?);
+        card
+    }
+
+    pub fn flush_draw(&mut self) -> Option<(Flop, Hand, Card)> {
+        let suit = Suit::get(random());
+        // hand
+        let card1 = self.deal_suit(suit)?;
+        let card2 = self.deal_suit(suit)?;
+        let hand = Hand([card1, card2]);
+        // board
+        let card3 = self.deal_suit(suit)?;
+        let card4 = self.deal_suit(suit)?;
+        // last card must not be same suit
+        let mut card5 = self._deal_one_random()?;
+        while card5.0 == suit {
+            card5 = self._deal_one_random()?;
+        }
+        self.remove_card(&card5);
+        let flop = Flop ([card3, card4, card5]);
+
+        //turn
+        let mut turn = self._deal_one_random()?;
+        while turn.0 == suit
+           || turn.1 == (hand.0[0]).1
+           || turn.1 == (hand.0[1]).1
+        {
+            turn = self._deal_one_random()?;
+        }
+        self.remove_card(&turn);
+
+        Some((flop, hand, turn))
+    }
+
+    pub fn hole_card(&mut self) -> Option<(Flop, Hand, Card)> {
+        let start = self.lowest + random::<u64>() % (8 - self.lowest);
+        // hand
+        let card1 = self.deal_rank(Rank::get(start))?;
+        let card2 = self.deal_rank(Rank::get(start+1))?;
+        let hand = Hand ([card1, card2]);
+        // board
+        let card3 = self.deal_rank(Rank::get(start+3))?;
+        let card4 = self.deal_rank(Rank::get(start+4))?;
+        let mut card5 = self._deal_one_random()?;
+        while card5.1 == Rank::get(start+2) {
+            card5 = self._deal_one_random()?;
+        }
+        self.remove_card(&card5);
+        let flop = Flop ([card3, card4, card5]);
+
+        //turn
+        let mut turn = self._deal_one_random()?;
+        while turn.1 == Rank::get(start+2)
+           || turn.1 == (hand.0[0]).1
+           || turn.1 == (hand.0[1]).1
+        {
+            turn = self._deal_one_random()?;
+        }
+        self.remove_card(&turn);
+
+        Some((flop, hand, turn))
+    }
+
+    pub fn de_straight(&mut self) -> Option<(Flop, Hand, Card)> {
+        let start = self.lowest + random::<u64>() % (7 - self.lowest);
+        // hand
+        let card1 = self.deal_rank(Rank::get(start+1))?;
+        let card2 = self.deal_rank(Rank::get(start+2))?;
+        let hand = Hand ([card1, card2]);
+        // board
+        let card3 = self.deal_rank(Rank::get(start+3))?;
+        let card4 = self.deal_rank(Rank::get(start+4))?;
+        let mut card5 = self.deal_one_rand()?;
+        while card5.1 == Rank::get(start+5)
+           || card5.1 == Rank::get(start)
+        {
+            card5 = self.deal_one_rand()?;
+        }
+        let flop = Flop ([card3, card4, card5]);
+
+        //turn
+        let mut turn = self._deal_one_random()?;
+        while turn.1 == Rank::get(start)
+           || turn.1 == Rank::get(start+5)
+           || turn.1 == (hand.0[0]).1
+           || turn.1 == (hand.0[1]).1
+        {
+            turn = self._deal_one_random()?;
+        }
+        self.remove_card(&turn);
+
+        Some((flop, hand, turn))
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_without() {
+        use card::parse_cards;
+        use config::Config;
+        use deck::Deck;
+
+        let config = Config::default();
+        let deck = Deck::without(&config, &parse_cards("AhKh2c").unwrap()).unwrap();
+        assert_eq!(49, deck.cards().len());
+        assert!(Deck::without(&config, &parse_cards("AhAh").unwrap()).is_err());
+        let short = Config { short_deck: true, ..Config::default() };
+        assert_eq!(36, Deck::for_config(&short).cards().len());
+        assert!(Deck::without(&short, &parse_cards("5c").unwrap()).is_err());
+    }
+}
@@ -0,0 +1,151 @@
+//! Questions that only depend on the pot and the bet size: how often the
+//! defender has to continue, and how the bettor should build a range.
+
+use rand::prelude::*;
+
+use config::Config;
+use odds;
+use pot::Pot;
+use quiz::{Ask, Quiz};
+
+/// Questions that only need the pot and the bet
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum DrillQuestion {
+    /// Share of hands the defender must continue with against a bet
+    MinimumDefense,
+    /// How often a pure bluff has to work
+    FoldEquity,
+    /// Bluffs per value hand in a balanced river bet, "1 to 2"
+    BluffToValue,
+}
+
+impl Ask for DrillQuestion {
+    fn prompt(&self) -> &'static str {
+        use self::DrillQuestion::*;
+        match self {
+            MinimumDefense => "What is the minimum defense frequency?",
+            FoldEquity => "How often must a pure bluff work?",
+            BluffToValue => "What river bluff to value ratio is balanced (x to 1)?",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Option<f32> {
+        use self::DrillQuestion::*;
+        match self {
+            MinimumDefense | FoldEquity => odds::parse_percent(input),
+            BluffToValue => odds::parse_ratio(input),
+        }
+    }
+
+    fn show(&self, x: f32, _: &Config) -> String {
+        use self::DrillQuestion::*;
+        match self {
+            MinimumDefense | FoldEquity => odds::format_percent(x),
+            BluffToValue => format!("{:.2} to 1", x),
+        }
+    }
+}
+
+/// Share of the range that must continue so a bluff can't profit
+pub fn minimum_defense(pot: f32, bet: f32) -> f32 {
+    pot / (pot + bet)
+}
+
+/// How often villain must fold for a pure bluff to break even
+pub fn bluff_fold_equity(pot: f32, bet: f32) -> f32 {
+    bet / (pot + bet)
+}
+
+/// Share of bluffs in a river betting range that leaves a call indifferent
+pub fn bluff_share(pot: f32, bet: f32) -> f32 {
+    bet / (pot + bet * 2.)
+}
+
+/// Bluffs per value hand, `bluff_share` as an "x to 1" ratio
+pub fn bluff_to_value(pot: f32, bet: f32) -> f32 {
+    bet / (pot + bet)
+}
+
+pub struct Drill {
+    pub question: DrillQuestion,
+    pub pot: Pot,
+    pub bet: f32,
+}
+
+impl Drill {
+    pub fn new(question: DrillQuestion, pot: Pot, bet: f32) -> Self {
+        Self {
+            question,
+            pot,
+            bet,
+        }
+    }
+
+    pub fn rand_drill(config: &Config) -> Self {
+        use self::DrillQuestion::*;
+        let question = match random::<u8>() % 3 {
+            0 => MinimumDefense,
+            1 => FoldEquity,
+            2 => BluffToValue,
+            _ => unimplemented!(),
+        };
+        let pot = Pot::rand_pot(config);
+        let bet = pot.rand_bet(config);
+        Drill::new(question, pot, bet)
+    }
+
+    pub fn answer(&self) -> f32 {
+        use self::DrillQuestion::*;
+        let (pot, bet) = (self.pot.size, self.bet);
+        match self.question {
+            MinimumDefense => minimum_defense(pot, bet),
+            FoldEquity => bluff_fold_equity(pot, bet),
+            BluffToValue => bluff_to_value(pot, bet),
+        }
+    }
+
+    pub fn quiz(&self) -> Quiz<DrillQuestion> {
+        let tolerance = match self.question {
+            DrillQuestion::BluffToValue => 0.05,
+            _ => 0.02,
+        };
+        Quiz::new(self.question, self.answer(), tolerance)
+    }
+
+    pub fn describe(&self, config: &Config) -> String {
+        use self::DrillQuestion::*;
+        let who = match self.question {
+            MinimumDefense => "You face a bet",
+            _ => "You bet",
+        };
+        format!("{} of {} into {}", who, config.money(self.bet), config.money(self.pot.size))
+    }
+
+    pub fn formula(&self, config: &Config) -> String {
+        use self::DrillQuestion::*;
+        let money = |x| config.money(x);
+        let (pot, bet) = (self.pot.size, self.bet);
+        let answer = self.question.show(self.answer(), config);
+        match self.question {
+            MinimumDefense => format!("MDF = pot / (pot + bet) = {} / ({} + {}) = {}",
+                money(pot), money(pot), money(bet), answer),
+            FoldEquity => format!("fold equity = bet / (pot + bet) = {} / ({} + {}) = {}",
+                money(bet), money(pot), money(bet), answer),
+            BluffToValue => format!("bluffs = bet / (pot + 2 x bet) = {} / {}, bluff : value = {} : {} = {}",
+                money(bet), money(pot + bet * 2.), money(bet), money(pot + bet), answer),
+        }
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_pot_sized_bet() {
+        use super::*;
+
+        assert_eq!(0.5, minimum_defense(100., 100.));
+        assert_eq!(0.5, bluff_fold_equity(100., 100.));
+        assert_eq!(1. / 3., bluff_share(100., 100.));
+        assert_eq!(0.5, bluff_to_value(100., 100.));
+        assert_eq!(0.75, minimum_defense(150., 50.));
+    }
+}
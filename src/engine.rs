@@ -0,0 +1,385 @@
+//! Heads-up no-limit hold'em: blinds, the four betting rounds with
+//! min-raise rules, and the showdown. Hands are dealt from a `Deck` up
+//! front and every action is logged, so a hand can be replayed from its
+//! cards and actions.
+
+use card::{Card, CardRepr, Hand};
+use config::Config;
+use deck::Deck;
+use evaluate::{evaluate7, evaluate_short_deck7};
+use history::{Action, Street};
+use pot::Pot;
+
+/// Seat 0 is the button. It posts the small blind, acts first before the
+/// flop and last after it.
+pub const BUTTON: usize = 0;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Player {
+    pub hand: Hand,
+    /// Chips behind
+    pub stack: f32,
+    /// Put in on this street
+    pub bet: f32,
+    pub folded: bool,
+}
+
+/// What the player to act may do
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Legal {
+    /// To match the other player, 0 when checking is allowed
+    pub call: f32,
+    /// Smallest and largest street total to bet or raise to, `None` when
+    /// the player can only call or fold
+    pub raise: Option<(f32, f32)>,
+}
+
+/// One action, `to` being the player's street total after it
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Logged {
+    pub seat: usize,
+    pub street: Street,
+    pub action: Action,
+    pub to: f32,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct Outcome {
+    /// One seat, or both on a split pot
+    pub winners: Vec<usize>,
+    /// What each winner got, after rake
+    pub won: f32,
+    pub rake: f32,
+    /// Ranks at showdown, `None` when someone folded
+    pub ranks: Option<[usize; 2]>,
+}
+
+pub struct Game {
+    pub players: [Player; 2],
+    pub small_blind: f32,
+    pub big_blind: f32,
+    /// Stacks before the blinds, to replay the hand
+    pub stacks: [f32; 2],
+    /// Chips from finished streets
+    pub pot: Pot,
+    /// All five cards, dealt up front and shown street by street
+    cards: [Card; 5],
+    pub street: Street,
+    pub to_act: usize,
+    /// Size of the last full bet or raise, the least the next raise adds
+    min_raise: f32,
+    /// Who has acted since the last bet or raise; blinds don't count
+    acted: [bool; 2],
+    short_deck: bool,
+    pub log: Vec<Logged>,
+    pub outcome: Option<Outcome>,
+}
+
+fn other(seat: usize) -> usize {
+    1 - seat
+}
+
+impl Game {
+    /// Deals a hand and posts the blinds
+    pub fn new(config: &Config, stacks: [f32; 2]) -> Self {
+        let mut deck = Deck::for_config(config);
+        let mut deal = || deck.deal_one_rand().unwrap();
+        let hands = [Hand([deal(), deal()]), Hand([deal(), deal()])];
+        let board = [deal(), deal(), deal(), deal(), deal()];
+        Game::with_cards(config, stacks, hands, board)
+    }
+
+    /// A hand with known cards, e.g. to replay one
+    pub fn with_cards(config: &Config, stacks: [f32; 2], hands: [Hand; 2], cards: [Card; 5]) -> Self {
+        let player = |seat: usize| Player {
+            hand: hands[seat],
+            stack: stacks[seat],
+            bet: 0.,
+            folded: false,
+        };
+        let mut ret = Self {
+            players: [player(0), player(1)],
+            small_blind: config.small_blind,
+            big_blind: config.big_blind,
+            stacks,
+            pot: Pot::with_rake(0., config.rake),
+            cards,
+            street: Street::Preflop,
+            to_act: BUTTON,
+            min_raise: config.big_blind,
+            acted: [false; 2],
+            short_deck: config.short_deck,
+            log: Vec::new(),
+            outcome: None,
+        };
+        ret.pot.flop_dealt = false;
+        ret.put_in(BUTTON, config.small_blind);
+        ret.put_in(other(BUTTON), config.big_blind);
+        ret
+    }
+
+    /// Plays `actions` from the start of a hand with known cards
+    pub fn replay(config: &Config, stacks: [f32; 2], hands: [Hand; 2], cards: [Card; 5], actions: &[Logged])
+        -> Result<Self, String>
+    {
+        let mut game = Game::with_cards(config, stacks, hands, cards);
+        for a in actions {
+            if a.seat != game.to_act || a.street != game.street {
+                return Err(format!("{:?} out of turn: {:?}", a.action, a));
+            }
+            game.act(a.action, a.to)?;
+        }
+        Ok(game)
+    }
+
+    pub fn cards(&self) -> [Card; 5] {
+        self.cards
+    }
+
+    /// The cards shown so far
+    pub fn board(&self) -> &[Card] {
+        match self.street {
+            Street::Preflop => &[],
+            Street::Flop => &self.cards[..3],
+            Street::Turn => &self.cards[..4],
+            Street::River => &self.cards[..],
+        }
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.outcome.is_some()
+    }
+
+    /// Everything in the middle, this street's bets included
+    pub fn pot_total(&self) -> f32 {
+        self.pot.size + self.players[0].bet + self.players[1].bet
+    }
+
+    /// Moves up to `amount` from a stack into its bet
+    fn put_in(&mut self, seat: usize, amount: f32) {
+        let player = &mut self.players[seat];
+        let amount = amount.min(player.stack);
+        player.stack -= amount;
+        player.bet += amount;
+    }
+
+    fn max_bet(&self) -> f32 {
+        self.players[0].bet.max(self.players[1].bet)
+    }
+
+    pub fn legal(&self) -> Legal {
+        let (me, them) = (&self.players[self.to_act], &self.players[other(self.to_act)]);
+        let call = (self.max_bet() - me.bet).min(me.stack);
+        // nobody left to raise against, or nothing to raise with
+        let raise = if them.stack > 0. && me.stack > call {
+            let all_in = me.bet + me.stack;
+            let most = all_in.min(them.bet + them.stack);
+            Some(((self.max_bet() + self.min_raise).min(most), most))
+        } else {
+            None
+        };
+        Legal { call, raise }
+    }
+
+    /// The actions open to the player to act, bets and raises being
+    /// allowed to any total within `Legal::raise`
+    pub fn actions(&self) -> Vec<Action> {
+        if self.is_over() {
+            return Vec::new();
+        }
+        let legal = self.legal();
+        let mut ret = Vec::new();
+        if legal.call > 0. {
+            ret.push(Action::Fold);
+            ret.push(Action::Call);
+        } else {
+            ret.push(Action::Check);
+        }
+        if legal.raise.is_some() {
+            ret.push(if self.max_bet() > 0. { Action::Raise } else { Action::Bet });
+        }
+        ret
+    }
+
+    /// Plays `action` for the player to act. `to` is the street total for
+    /// a bet or raise and is ignored otherwise.
+    pub fn act(&mut self, action: Action, to: f32) -> Result<(), String> {
+        if !self.actions().contains(&action) {
+            return Err(format!("can't {:?} now", action));
+        }
+        let seat = self.to_act;
+        let legal = self.legal();
+        match action {
+            Action::Fold => self.players[seat].folded = true,
+            Action::Check => {}
+            Action::Call => self.put_in(seat, legal.call),
+            Action::Bet | Action::Raise => {
+                let (least, most) = legal.raise.unwrap();
+                if to < least || to > most {
+                    return Err(format!("{:?} must be to between {} and {}", action, least, most));
+                }
+                let raise = to - self.max_bet();
+                // an all in short of a full raise doesn't change the minimum
+                if raise >= self.min_raise {
+                    self.min_raise = raise;
+                }
+                let amount = to - self.players[seat].bet;
+                self.put_in(seat, amount);
+                self.acted = [false; 2];
+            }
+        }
+        self.acted[seat] = true;
+        self.log.push(Logged {
+            seat,
+            street: self.street,
+            action,
+            to: self.players[seat].bet,
+        });
+
+        if action == Action::Fold {
+            self.finish();
+        } else {
+            self.to_act = other(seat);
+            self.next_street_if_done();
+        }
+        Ok(())
+    }
+
+    /// Deals the next street once both players have acted and matched the
+    /// bets, or runs the board out when someone is all in
+    fn next_street_if_done(&mut self) {
+        let max = self.max_bet();
+        let done = |seat: usize| {
+            let p = &self.players[seat];
+            p.stack == 0. || (self.acted[seat] && p.bet == max)
+        };
+        if !(done(0) && done(1)) {
+            return;
+        }
+        self.collect_bets();
+        self.acted = [false; 2];
+        self.min_raise = self.big_blind;
+        self.to_act = other(BUTTON);
+        self.pot.flop_dealt = true;
+        if self.street == Street::River || self.players.iter().any(|p| p.stack == 0.) {
+            self.street = Street::River;
+            return self.finish();
+        }
+        self.street = match self.street {
+            Street::Preflop => Street::Flop,
+            Street::Flop => Street::Turn,
+            _ => Street::River,
+        };
+    }
+
+    /// Moves the bets into the pot, giving back the part of a bet the
+    /// other player couldn't match
+    fn collect_bets(&mut self) {
+        let (i, j) = if self.players[0].bet > self.players[1].bet { (0, 1) } else { (1, 0) };
+        let extra = self.players[i].bet - self.players[j].bet;
+        self.players[i].stack += extra;
+        self.pot.size += self.players[j].bet * 2.;
+        self.players[0].bet = 0.;
+        self.players[1].bet = 0.;
+    }
+
+    fn rank(&self, seat: usize) -> usize {
+        let Hand([a, b]) = self.players[seat].hand;
+        let c: Vec<usize> = self.cards.iter().map(|c| c.to_int()).collect();
+        if self.short_deck {
+            evaluate_short_deck7([a.to_int(), b.to_int(), c[0], c[1], c[2], c[3], c[4]])
+        } else {
+            evaluate7(a.to_int(), b.to_int(), c[0], c[1], c[2], c[3], c[4])
+        }
+    }
+
+    /// Pays the pot to the player left in or the best hand
+    fn finish(&mut self) {
+        self.collect_bets();
+
+        let folded = self.players.iter().position(|p| p.folded);
+        let (winners, ranks) = match folded {
+            Some(seat) => (vec![other(seat)], None),
+            None => {
+                let ranks = [self.rank(0), self.rank(1)];
+                let winners = if ranks[0] == ranks[1] {
+                    vec![0, 1]
+                } else if ranks[0] < ranks[1] {
+                    vec![0]
+                } else {
+                    vec![1]
+                };
+                (winners, Some(ranks))
+            }
+        };
+        let rake = self.pot.rake.taken(self.pot.size, self.pot.flop_dealt);
+        let won = (self.pot.size - rake) / winners.len() as f32;
+        for &seat in &winners {
+            self.players[seat].stack += won;
+        }
+        self.outcome = Some(Outcome { winners, won, rake, ranks });
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_betting() {
+        use card::{parse_cards, Hand};
+        use config::Config;
+        use engine::*;
+        use history::{Action, Street};
+        use rake::Rake;
+
+        let config = Config { small_blind: 1., big_blind: 2., rake: Rake::none(), ..Config::default() };
+        let hands: [Hand; 2] = ["AhKh".parse().unwrap(), "QsQd".parse().unwrap()];
+        let cards = parse_cards("2h7h9cTsKd").unwrap();
+        let cards = [cards[0], cards[1], cards[2], cards[3], cards[4]];
+        let mut game = Game::with_cards(&config, [100., 100.], hands, cards);
+
+        assert_eq!(BUTTON, game.to_act);
+        assert_eq!(vec![Action::Fold, Action::Call, Action::Raise], game.actions());
+        assert_eq!(Legal { call: 1., raise: Some((4., 100.)) }, game.legal());
+        assert!(game.act(Action::Raise, 3.).is_err());
+        game.act(Action::Raise, 6.).unwrap();
+        // the big blind has to raise by at least the 4 the button did
+        assert_eq!(Some((10., 100.)), game.legal().raise);
+        game.act(Action::Call, 0.).unwrap();
+
+        assert_eq!((Street::Flop, 12.), (game.street, game.pot.size));
+        assert_eq!(3, game.board().len());
+        assert_eq!(1, game.to_act);
+        assert_eq!(vec![Action::Check, Action::Bet], game.actions());
+        game.act(Action::Check, 0.).unwrap();
+        game.act(Action::Bet, 8.).unwrap();
+        game.act(Action::Raise, 24.).unwrap();
+        assert_eq!(Some((40., 94.)), game.legal().raise);
+        game.act(Action::Call, 0.).unwrap();
+
+        // turn and river checked through, kings beat queens
+        for _ in 0..4 {
+            game.act(Action::Check, 0.).unwrap();
+        }
+        let outcome = game.outcome.clone().unwrap();
+        assert_eq!(vec![0], outcome.winners);
+        assert_eq!(60., outcome.won);
+        assert_eq!([130., 70.], [game.players[0].stack, game.players[1].stack]);
+
+        let replayed = Game::replay(&config, game.stacks, hands, cards, &game.log).unwrap();
+        assert_eq!(Some(outcome), replayed.outcome);
+
+        // a fold gives the pot to the other player without a showdown
+        let mut game = Game::with_cards(&config, [100., 100.], hands, cards);
+        game.act(Action::Raise, 6.).unwrap();
+        game.act(Action::Fold, 0.).unwrap();
+        assert_eq!([102., 98.], [game.players[0].stack, game.players[1].stack]);
+        assert_eq!(None, game.outcome.unwrap().ranks);
+
+        // all in and called runs out the board
+        let mut game = Game::with_cards(&config, [50., 100.], hands, cards);
+        game.act(Action::Raise, 50.).unwrap();
+        assert_eq!(Legal { call: 48., raise: None }, game.legal());
+        game.act(Action::Call, 0.).unwrap();
+        assert_eq!(Street::River, game.street);
+        assert_eq!([100., 50.], [game.players[0].stack, game.players[1].stack]);
+    }
+}
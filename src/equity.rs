@@ -0,0 +1,747 @@
+//! All-in equity by enumerating every way the board can run out.
+//!
+//! With the `parallel` feature the runouts are split over one thread per
+//! core, unless there are too few to be worth starting threads. Every
+//! thread counts whole showdowns and the counts are added up, so the
+//! results don't depend on the number of threads.
+
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+#[cfg(feature = "parallel")]
+use std::thread;
+
+use card::{Card, CardRepr, Hand, OmahaHand};
+#[cfg(feature = "parallel")]
+use evaluate;
+use evaluate::{evaluate7, evaluate_omaha, evaluate_omaha_low8, EvalState};
+use range::{HandClass, Range};
+
+/// Showdowns won and tied by one side of a matchup
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Tally {
+    pub win: u64,
+    pub tie: u64,
+    pub total: u64,
+}
+
+impl Tally {
+    /// Ties count as half a win
+    pub fn equity(&self) -> f32 {
+        (self.win as f64 + self.tie as f64 / 2.) as f32 / self.total as f32
+    }
+
+    fn add(&mut self, hero: usize, villain: usize) {
+        self.total += 1;
+        if hero < villain {
+            self.win += 1;
+        } else if hero == villain {
+            self.tie += 1;
+        }
+    }
+}
+
+/// Each player's share of a hi-lo pot.
+///
+/// The best high hands split one half and the best qualifying lows the
+/// other, so a player tying the low and winning the high is quartered on
+/// one side and takes three quarters. Without a qualifying low the high
+/// hands take it all.
+pub fn split_shares(high: &[usize], low: &[Option<usize>]) -> Vec<f32> {
+    let best_high = high.iter().min().cloned();
+    let best_low = low.iter().filter_map(|&l| l).min();
+    let high_pot = if best_low.is_some() { 0.5 } else { 1. };
+    let high_winners = high.iter().filter(|&&h| Some(h) == best_high).count() as f32;
+    let low_winners = low.iter().filter(|&&l| l.is_some() && l == best_low).count() as f32;
+    high.iter().zip(low)
+        .map(|(&h, &l)| {
+            let mut share = 0.;
+            if Some(h) == best_high {
+                share += high_pot / high_winners;
+            }
+            if l.is_some() && l == best_low {
+                share += 0.5 / low_winners;
+            }
+            share
+        })
+        .collect()
+}
+
+/// Hero's hi-lo showdowns by share of the pot won, in quarters
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct Split {
+    pub quarters: [u64; 5],
+    pub total: u64,
+}
+
+impl Split {
+    pub fn equity(&self) -> f32 {
+        let won: u64 = self.quarters.iter().enumerate().map(|(i, &n)| i as u64 * n).sum();
+        won as f32 / 4. / self.total as f32
+    }
+
+    /// Chance of winning `quarters` quarters of the pot
+    pub fn chance(&self, quarters: usize) -> f32 {
+        self.quarters[quarters] as f32 / self.total as f32
+    }
+
+    fn add(&mut self, share: f32) {
+        self.total += 1;
+        self.quarters[(share * 4.).round() as usize] += 1;
+    }
+}
+
+/// Each player's share of the pots in a multiway all in, in `Shares::UNIT`
+/// parts of a pot so that split pots stay whole numbers
+#[derive(PartialEq, Eq, Debug, Default, Clone)]
+pub struct Shares {
+    pub won: Vec<u64>,
+    pub total: u64,
+}
+
+impl Shares {
+    /// Divides by every number of players up to ten
+    pub const UNIT: u64 = 2520;
+
+    pub fn equity(&self, player: usize) -> f32 {
+        self.won[player] as f32 / Shares::UNIT as f32 / self.total as f32
+    }
+
+    fn add(&mut self, ranks: &[usize]) {
+        let best = ranks.iter().min().cloned();
+        let winners = ranks.iter().filter(|&&r| Some(r) == best).count() as u64;
+        self.won.resize(ranks.len(), 0);
+        for (won, &r) in self.won.iter_mut().zip(ranks) {
+            if Some(r) == best {
+                *won += Shares::UNIT / winners;
+            }
+        }
+        self.total += 1;
+    }
+}
+
+/// Counts that add up over parts of the boards
+trait Count: Default + Send + 'static {
+    fn merge(&mut self, other: &Self);
+}
+
+impl Count for Tally {
+    fn merge(&mut self, other: &Tally) {
+        self.win += other.win;
+        self.tie += other.tie;
+        self.total += other.total;
+    }
+}
+
+impl Count for Split {
+    fn merge(&mut self, other: &Split) {
+        for (q, o) in self.quarters.iter_mut().zip(&other.quarters) {
+            *q += o;
+        }
+        self.total += other.total;
+    }
+}
+
+impl Count for Shares {
+    fn merge(&mut self, other: &Shares) {
+        self.won.resize(other.won.len().max(self.won.len()), 0);
+        for (w, o) in self.won.iter_mut().zip(&other.won) {
+            *w += o;
+        }
+        self.total += other.total;
+    }
+}
+
+/// Equities with weights, e.g. against each combo of a range, lowest first
+#[derive(PartialEq, Debug, Clone)]
+pub struct EquityDistribution {
+    /// (equity, weight)
+    pub equities: Vec<(f32, f32)>,
+}
+
+impl EquityDistribution {
+    /// Drops hands without weight and NaN equities, which leaves nothing
+    /// the sort can't order
+    pub fn new(mut equities: Vec<(f32, f32)>) -> Self {
+        equities.retain(|&(e, w)| w > 0. && !e.is_nan());
+        equities.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+        Self { equities }
+    }
+
+    fn total(&self) -> f32 {
+        self.equities.iter().map(|e| e.1).sum()
+    }
+
+    pub fn mean(&self) -> Option<f32> {
+        let total = self.total();
+        if total > 0. {
+            Some(self.equities.iter().map(|&(e, w)| e * w).sum::<f32>() / total)
+        } else {
+            None
+        }
+    }
+
+    /// Share of the weight in each of `buckets` equal slices of 0 to 100%,
+    /// all zero when there is no weight
+    pub fn histogram(&self, buckets: usize) -> Vec<f32> {
+        let mut ret = vec![0.; buckets];
+        let total = self.total();
+        if buckets == 0 || total <= 0. {
+            return ret;
+        }
+        for &(e, w) in &self.equities {
+            let i = ((e * buckets as f32) as usize).min(buckets - 1);
+            ret[i] += w / total;
+        }
+        ret
+    }
+
+    /// Lowest equity with at least `p` of the weight at or below it
+    pub fn percentile(&self, p: f32) -> Option<f32> {
+        let target = p * self.total();
+        let mut seen = 0.;
+        for &(e, w) in &self.equities {
+            seen += w;
+            if seen >= target {
+                return Some(e);
+            }
+        }
+        self.equities.last().map(|e| e.0)
+    }
+}
+
+fn mask(cards: &[Card]) -> u64 {
+    cards.iter().fold(0, |m, c| m | 1 << c.to_int())
+}
+
+/// Calls `f` with every five card board that starts with `board` and is
+/// completed from the cards not in `dead`
+pub fn for_each_board<F: FnMut(&[usize; 5])>(board: &[Card], dead: u64, f: F) {
+    for_each_board_part(board, dead, 0, 1, f);
+}
+
+/// The boards of `for_each_board` whose first dealt card is at a position
+/// in the remaining deck equal to `part` modulo `parts`
+pub fn for_each_board_part<F>(board: &[Card], dead: u64, part: usize, parts: usize, mut f: F)
+    where F: FnMut(&[usize; 5])
+{
+    let mut ids = [0; 5];
+    for (i, c) in board.iter().enumerate() {
+        ids[i] = c.to_int();
+    }
+    let dead = dead | mask(board);
+    let deck: Vec<usize> = (0..52).filter(|i| dead & 1 << i == 0).collect();
+    let i = board.len();
+    if i == 5 {
+        if part == 0 {
+            f(&ids);
+        }
+        return;
+    }
+    for j in (part..deck.len() + i + 1 - 5).step_by(parts) {
+        ids[i] = deck[j];
+        deal(&deck, &mut ids, i + 1, j + 1, &mut f);
+    }
+}
+
+/// Worker threads for enumeration, one per core with the `parallel` feature
+#[cfg(feature = "parallel")]
+pub fn threads() -> usize {
+    thread::available_parallelism().map(|n| n.get()).unwrap_or(1)
+}
+
+#[cfg(not(feature = "parallel"))]
+pub fn threads() -> usize {
+    1
+}
+
+/// Showdowns below which starting threads costs more than it saves
+const SERIAL_WORK: u64 = 100_000;
+
+/// Parts to split `work` showdowns in: one per thread, or a single part
+/// run on the calling thread when there is too little work
+fn parts_for(work: u64) -> usize {
+    if work < SERIAL_WORK { 1 } else { threads() }
+}
+
+/// Boards `for_each_board` deals from `board` with the cards in `dead` out
+fn board_count(board: &[Card], dead: u64) -> u64 {
+    let deck = 52 - u64::from((dead | mask(board)).count_ones());
+    let left = 5 - board.len() as u64;
+    (0..left).fold(1, |n, i| n * (deck - i) / (i + 1))
+}
+
+/// Runs `work` on each of `parts` parts and returns the results in order.
+/// A single part runs on the calling thread.
+#[cfg(feature = "parallel")]
+fn run_parts<T, F>(parts: usize, work: F) -> Vec<T>
+    where T: Send + 'static, F: Fn(usize) -> T + Send + Sync + 'static
+{
+    if parts <= 1 {
+        return (0..parts).map(work).collect();
+    }
+    // the tables are built once here rather than by the first thread
+    // that needs them while the others wait
+    evaluate::init();
+    let work = Arc::new(work);
+    let handles: Vec<_> = (0..parts)
+        .map(|part| {
+            let work = work.clone();
+            thread::spawn(move || work(part))
+        })
+        .collect();
+    handles.into_iter().map(|h| h.join().unwrap()).collect()
+}
+
+#[cfg(not(feature = "parallel"))]
+fn run_parts<T, F>(parts: usize, work: F) -> Vec<T>
+    where F: Fn(usize) -> T
+{
+    (0..parts).map(work).collect()
+}
+
+/// Adds up `showdown` over the boards of `for_each_board`, split in
+/// `parts` that run on their own threads with the `parallel` feature
+fn count_boards<T, F>(board: &[Card], dead: u64, parts: usize, showdown: F) -> T
+    where T: Count, F: Fn(&mut T, &[usize; 5]) + Send + Sync + 'static
+{
+    let board = board.to_vec();
+    let results = run_parts(parts, move |part| {
+        let mut count = T::default();
+        for_each_board_part(&board, dead, part, parts, |b| showdown(&mut count, b));
+        count
+    });
+    results.iter().fold(T::default(), |mut count, part| {
+        count.merge(part);
+        count
+    })
+}
+
+fn deal<F: FnMut(&[usize; 5])>(deck: &[usize], ids: &mut [usize; 5], i: usize, from: usize, f: &mut F) {
+    if i == 5 {
+        f(ids);
+        return;
+    }
+    for j in from..deck.len() + i + 1 - 5 {
+        ids[i] = deck[j];
+        deal(deck, ids, i + 1, j + 1, f);
+    }
+}
+
+/// State holding `cards` and the known board
+fn state(cards: &[Card], board: &[Card]) -> EvalState {
+    cards.iter().chain(board).fold(EvalState::new(), |s, c| s.add(c.to_int()))
+}
+
+/// Deals the `left` board cards still to come from `deck[from..]`, one
+/// street at a time: every state gets the turn added once and each river
+/// then only adds itself. The states after each card go in the next of
+/// `bufs`, one per card to come, so no board allocates. Calls `f` with the
+/// finished states and a mask of the cards dealt.
+fn deal_states<F>(deck: &[usize], states: &[EvalState], bufs: &mut [Vec<EvalState>], left: usize, from: usize, dealt: u64, f: &mut F)
+    where F: FnMut(&[EvalState], u64)
+{
+    if left == 0 {
+        f(states, dealt);
+        return;
+    }
+    let (next, bufs) = bufs.split_first_mut().unwrap();
+    for j in from..deck.len() + 1 - left {
+        next.clear();
+        next.extend(states.iter().map(|s| s.add(deck[j])));
+        deal_states(deck, next, bufs, left - 1, j + 1, dealt | 1 << deck[j], f);
+    }
+}
+
+fn rank(hand: &Hand, b: &[usize; 5]) -> usize {
+    evaluate7(hand.0[0].to_int(), hand.0[1].to_int(), b[0], b[1], b[2], b[3], b[4])
+}
+
+pub fn hand_vs_hand(hero: &Hand, villain: &Hand, board: &[Card]) -> Tally {
+    let (hero, villain) = (*hero, *villain);
+    let dead = mask(&hero.0) | mask(&villain.0);
+    count_boards(board, dead, parts_for(board_count(board, dead)), move |tally: &mut Tally, b| {
+        tally.add(rank(&hero, b), rank(&villain, b));
+    })
+}
+
+/// Every player's share of the pot, ties split evenly. Up to ten hands.
+pub fn multiway(hands: &[Hand], board: &[Card]) -> Shares {
+    assert!(hands.len() <= 10, "can't split a pot {} ways", hands.len());
+    let dead = hands.iter().fold(0, |m, h| m | mask(&h.0));
+    let hands = hands.to_vec();
+    let parts = parts_for(board_count(board, dead) * hands.len() as u64);
+    count_boards(board, dead, parts, move |shares: &mut Shares, b| {
+        let ranks: Vec<usize> = hands.iter().map(|h| rank(h, b)).collect();
+        shares.add(&ranks);
+    })
+}
+
+fn omaha_rank(hand: &OmahaHand, b: &[usize; 5]) -> usize {
+    let h = &hand.0;
+    evaluate_omaha([h[0].to_int(), h[1].to_int(), h[2].to_int(), h[3].to_int()], b)
+}
+
+pub fn omaha_vs_omaha(hero: &OmahaHand, villain: &OmahaHand, board: &[Card]) -> Tally {
+    let (hero, villain) = (*hero, *villain);
+    let dead = mask(&hero.0) | mask(&villain.0);
+    // each hand ranks sixty ways to play two hole and three board cards
+    count_boards(board, dead, parts_for(board_count(board, dead) * 60), move |tally: &mut Tally, b| {
+        tally.add(omaha_rank(&hero, b), omaha_rank(&villain, b));
+    })
+}
+
+fn omaha_low(hand: &OmahaHand, b: &[usize; 5]) -> Option<usize> {
+    let h = &hand.0;
+    evaluate_omaha_low8([h[0].to_int(), h[1].to_int(), h[2].to_int(), h[3].to_int()], b)
+}
+
+/// Omaha hi-lo eight or better
+pub fn omaha8_vs_omaha8(hero: &OmahaHand, villain: &OmahaHand, board: &[Card]) -> Split {
+    let (hero, villain) = (*hero, *villain);
+    let dead = mask(&hero.0) | mask(&villain.0);
+    count_boards(board, dead, parts_for(board_count(board, dead) * 120), move |split: &mut Split, b| {
+        let high = [omaha_rank(&hero, b), omaha_rank(&villain, b)];
+        let low = [omaha_low(&hero, b), omaha_low(&villain, b)];
+        split.add(split_shares(&high, &low)[0]);
+    })
+}
+
+/// Hero's equity against each weighted villain hand over the boards it
+/// doesn't block, as (hand, weight, equity). Hands that collide with hero
+/// or the board are left out.
+pub fn equity_by_combo(hero: &Hand, villains: &[(Hand, f32)], board: &[Card]) -> Vec<(Hand, f32, f32)> {
+    let villains = live_villains(hero, villains, board);
+    let work = board_count(board, mask(&hero.0)) * (villains.len() as u64 + 1);
+    combo_equities(*hero, &villains, board, parts_for(work))
+}
+
+/// The villain hands with weight that share no card with hero or the
+/// board, with their masks
+fn live_villains(hero: &Hand, villains: &[(Hand, f32)], board: &[Card]) -> Vec<(Hand, f32, u64)> {
+    let known = mask(&hero.0) | mask(board);
+    villains.iter()
+        .map(|&(h, w)| (h, w, mask(&h.0)))
+        .filter(|&(_, w, m)| w > 0. && known & m == 0)
+        .collect()
+}
+
+/// `equity_by_combo` over live villain hands, the boards split in `parts`
+fn combo_equities(hero: Hand, villains: &[(Hand, f32, u64)], board: &[Card], parts: usize) -> Vec<(Hand, f32, f32)> {
+    if villains.is_empty() {
+        return Vec::new();
+    }
+    let tallies = combo_tallies(hero, villains.to_vec(), board.to_vec(), parts);
+    villains.iter().zip(&tallies).map(|(v, t)| (v.0, v.1, t.equity())).collect()
+}
+
+/// Hero's weighted equity against villain hands, see `equity_by_combo`.
+/// `None` if no hand is left.
+pub fn hand_vs_combos(hero: &Hand, villains: &[(Hand, f32)], board: &[Card]) -> Option<f32> {
+    distribution(hero, villains, board).mean()
+}
+
+/// How hero's equity spreads over the villain hands
+pub fn distribution(hero: &Hand, villains: &[(Hand, f32)], board: &[Card]) -> EquityDistribution {
+    EquityDistribution::new(equity_by_combo(hero, villains, board).into_iter().map(|(_, w, e)| (e, w)).collect())
+}
+
+/// Hero's showdowns against each villain hand, the boards split in `parts`
+fn combo_tallies(hero: Hand, villains: Vec<(Hand, f32, u64)>, board: Vec<Card>, parts: usize) -> Vec<Tally> {
+    let n = villains.len();
+    let results = run_parts(parts, move |part| {
+        // the known board goes in once, hero first then each villain
+        let mut states = vec![state(&hero.0, &board)];
+        states.extend(villains.iter().map(|v| state(&(v.0).0, &board)));
+        let dead = mask(&hero.0) | mask(&board);
+        let deck: Vec<usize> = (0..52).filter(|i| dead & 1 << i == 0).collect();
+        let left = 5 - board.len();
+        let mut tallies = vec![Tally::default(); villains.len()];
+        let mut showdown = |states: &[EvalState], dealt: u64| {
+            let hero_rank = states[0].rank();
+            for ((tally, v), s) in tallies.iter_mut().zip(&villains).zip(&states[1..]) {
+                if dealt & v.2 == 0 {
+                    tally.add(hero_rank, s.rank());
+                }
+            }
+        };
+        if left == 0 {
+            if part == 0 {
+                showdown(&states, 0);
+            }
+            return tallies;
+        }
+        let mut bufs = vec![Vec::with_capacity(states.len()); left];
+        let (next, bufs) = bufs.split_first_mut().unwrap();
+        // the first card dealt picks the part, as in `for_each_board_part`
+        for j in (part..deck.len() + 1 - left).step_by(parts) {
+            next.clear();
+            next.extend(states.iter().map(|s| s.add(deck[j])));
+            deal_states(&deck, next, bufs, left - 1, j + 1, 1 << deck[j], &mut showdown);
+        }
+        tallies
+    });
+    let mut tallies = vec![Tally::default(); n];
+    for part in results {
+        for (tally, t) in tallies.iter_mut().zip(&part) {
+            tally.merge(t);
+        }
+    }
+    tallies
+}
+
+pub fn hand_vs_range(hero: &Hand, range: &Range, board: &[Card]) -> Option<f32> {
+    hand_vs_combos(hero, &range.combos(), board)
+}
+
+/// Hero's range against villain's, each pair of combos that share no card
+/// weighted by both weights. `None` if no pair is left.
+///
+/// Every pair runs out every board, so this is for the flop on; before it
+/// `preflop` has the table.
+pub fn range_vs_range(hero: &Range, villain: &Range, board: &[Card]) -> Option<f32> {
+    let heroes: Vec<(Hand, f32)> = hero.combos().into_iter()
+        .filter(|&(h, w)| w > 0. && mask(&h.0) & mask(board) == 0)
+        .collect();
+    let villains = villain.combos();
+    let work = board_count(board, 0) * heroes.len() as u64 * villains.len() as u64;
+    // hero's combos are split over the threads once, each then running
+    // its combos' boards on its own
+    let parts = parts_for(work).min(heroes.len().max(1));
+    let board = board.to_vec();
+    let results = run_parts(parts, move |part| {
+        heroes.iter().enumerate().skip(part).step_by(parts)
+            .map(|(i, &(hand, w))| {
+                let villains = live_villains(&hand, &villains, &board);
+                let (mut sum, mut total) = (0., 0.);
+                for (_, v, e) in combo_equities(hand, &villains, &board, 1) {
+                    sum += w * v * e;
+                    total += w * v;
+                }
+                (i, sum, total)
+            })
+            .collect::<Vec<_>>()
+    });
+    // added up in hero's order, so the sum doesn't depend on the threads
+    let mut sums: Vec<(usize, f32, f32)> = results.into_iter().flatten().collect();
+    sums.sort_by_key(|s| s.0);
+    let (sum, total) = sums.iter().fold((0., 0.), |(sum, total), s| (sum + s.1, total + s.2));
+    if total > 0. { Some(sum / total) } else { None }
+}
+
+/// Hero's equity against each hand class, by `HandClass::index`. `None`
+/// where hero and the board hold cards of every combo.
+pub fn equity_by_class(hero: &Hand, board: &[Card]) -> Vec<Option<f32>> {
+    let combos: Vec<(Hand, f32)> = HandClass::all().iter()
+        .flat_map(|c| c.combos())
+        .map(|h| (h, 1.))
+        .collect();
+    let mut sums = vec![(0., 0.); 169];
+    for (villain, w, e) in equity_by_combo(hero, &combos, board) {
+        let sum = &mut sums[HandClass::of(&villain).index()];
+        sum.0 += e * w;
+        sum.1 += w;
+    }
+    sums.iter().map(|&(e, w)| if w > 0. { Some(e / w) } else { None }).collect()
+}
+
+/// Preflop equity of one hand class against another, e.g. AKo vs QQ.
+///
+/// Every combo of a class is a suit relabeling of any other, so one of
+/// hero's combos against all of villain's averages the same as every pair.
+pub fn class_vs_class(hero: HandClass, villain: HandClass) -> Option<f32> {
+    let combos: Vec<(Hand, f32)> = villain.combos().into_iter().map(|h| (h, 1.)).collect();
+    hand_vs_combos(&hero.combos()[0], &combos, &[])
+}
+
+mod tests {
+    #[test]
+    fn test_river_and_turn() {
+        use card::parse_cards;
+        use equity::{hand_vs_hand, hand_vs_range};
+
+        let board = parse_cards("2c7d9hTs").unwrap();
+        let tally = hand_vs_hand(&"AhKh".parse().unwrap(), &"8s8c".parse().unwrap(), &board);
+        assert_eq!(44, tally.total);
+        // only an ace or a king beats villain's eights
+        assert_eq!(6, tally.win);
+        assert_eq!(0, tally.tie);
+
+        let board = parse_cards("2c7d9hTsJs").unwrap();
+        let range = "QQ, 88".parse().unwrap();
+        let equity = hand_vs_range(&"AhKh".parse().unwrap(), &range, &board).unwrap();
+        assert_eq!(0., equity);
+        let range = "AK".parse().unwrap();
+        let equity = hand_vs_range(&"AcKc".parse().unwrap(), &range, &board).unwrap();
+        assert_eq!(0.5, equity);
+    }
+
+    #[test]
+    fn test_equity_by_class() {
+        use card::parse_cards;
+        use equity::equity_by_class;
+        use range::HandClass;
+
+        let board = parse_cards("AdKc7h2s3d").unwrap();
+        let equities = equity_by_class(&"AhAs".parse().unwrap(), &board);
+        let get = |s: &str| equities[s.parse::<HandClass>().unwrap().index()];
+        // one ace left
+        assert_eq!(None, get("AA"));
+        assert_eq!(Some(1.), get("KK"));
+        // the wheel
+        assert_eq!(Some(0.), get("54s"));
+    }
+
+    #[test]
+    fn test_distribution() {
+        use card::parse_cards;
+        use equity::{distribution, EquityDistribution};
+
+        let dist = EquityDistribution::new(vec![(0.9, 1.), (0.1, 3.), (0.5, 0.)]);
+        assert_eq!(vec![(0.1, 3.), (0.9, 1.)], dist.equities);
+        assert_eq!(Some(0.1), dist.percentile(0.5));
+        assert_eq!(Some(0.9), dist.percentile(0.9));
+        assert_eq!(vec![0.75, 0., 0., 0.25], dist.histogram(4));
+        assert!(dist.histogram(0).is_empty());
+        let empty = EquityDistribution::new(vec![(f32::NAN, 1.), (0.5, f32::NAN)]);
+        assert!(empty.equities.is_empty());
+        assert_eq!(vec![0., 0.], empty.histogram(2));
+        assert!((dist.mean().unwrap() - 0.3).abs() < 1e-6);
+
+        // a set is way ahead of one pair and way behind the nut straight
+        let board = parse_cards("9h8h2c7s").unwrap();
+        let villains: Vec<_> = ["JhTh", "6d5d", "AsAd", "KcKd"].iter()
+            .map(|s| (s.parse().unwrap(), 1.))
+            .collect();
+        let dist = distribution(&"2d2s".parse().unwrap(), &villains, &board);
+        assert_eq!(4, dist.equities.len());
+        assert!(dist.equities[0].0 < 0.25 && dist.equities[3].0 > 0.9);
+    }
+
+    #[test]
+    fn test_parts() {
+        use card::{parse_cards, Hand};
+        use equity::{combo_tallies, mask};
+
+        let hero: Hand = "AhKh".parse().unwrap();
+        let villains: Vec<(Hand, f32, u64)> = ["8s8c", "QdJd", "7c2d"].iter()
+            .map(|s| s.parse::<Hand>().unwrap())
+            .map(|h| (h, 1., mask(&h.0)))
+            .collect();
+        let board = parse_cards("2c7d9h").unwrap();
+        // same counts however the runouts are split
+        let whole = combo_tallies(hero, villains.clone(), board.clone(), 1);
+        assert_eq!(whole, combo_tallies(hero, villains.clone(), board.clone(), 3));
+        assert_eq!(whole, combo_tallies(hero, villains, board, 7));
+        // turns and rivers without villain's eights
+        assert_eq!(47 * 46 / 2 - 46 - 46 + 1, whole[0].total);
+    }
+
+    #[test]
+    fn test_count_boards() {
+        use card::{parse_cards, Hand, OmahaHand};
+        use equity::{board_count, count_boards, hand_vs_hand, mask, multiway, parts_for, rank, Shares, Split, Tally};
+
+        // one part is the serial count, more run on threads with the
+        // `parallel` feature, and the counts must not change
+        let hero: Hand = "AhKh".parse().unwrap();
+        let villain: Hand = "8s8c".parse().unwrap();
+        let board = parse_cards("2c7d9h").unwrap();
+        let dead = mask(&hero.0) | mask(&villain.0);
+        let heads_up = |parts| count_boards(&board, dead, parts, move |t: &mut Tally, b| t.add(rank(&hero, b), rank(&villain, b)));
+        let whole = heads_up(1);
+        assert_eq!(whole, heads_up(4));
+        assert_eq!(whole, heads_up(9));
+        assert_eq!(whole, hand_vs_hand(&hero, &villain, &board));
+        // a flop runs out too few boards to start threads for
+        assert_eq!(45 * 44 / 2, board_count(&board, dead));
+        assert_eq!(1, parts_for(board_count(&board, dead)));
+
+        let hands: Vec<Hand> = ["AhKh", "8s8c", "QdJd"].iter().map(|s| s.parse().unwrap()).collect();
+        let dead = hands.iter().fold(0, |m, h| m | mask(&h.0));
+        let three_way = |parts| {
+            let hands = hands.clone();
+            count_boards(&board, dead, parts, move |s: &mut Shares, b| {
+                s.add(&hands.iter().map(|h| rank(h, b)).collect::<Vec<_>>())
+            })
+        };
+        let whole = three_way(1);
+        assert_eq!(whole, three_way(5));
+        assert_eq!(whole, multiway(&hands, &board));
+        assert_eq!(43 * 42 / 2, whole.total);
+        assert_eq!(whole.total * Shares::UNIT, whole.won.iter().sum::<u64>());
+        let sum: f32 = (0..3).map(|i| whole.equity(i)).sum();
+        assert!((sum - 1.).abs() < 1e-5);
+
+        let hero: OmahaHand = "Ah2h3c4d".parse().unwrap();
+        let villain: OmahaHand = "KsKd5c6c".parse().unwrap();
+        let board = parse_cards("7h8sQd").unwrap();
+        let dead = mask(&hero.0) | mask(&villain.0);
+        let hi_lo = |parts| count_boards(&board, dead, parts, move |s: &mut Split, b| s.add([0.25, 0.5, 1.][b[3] % 3]));
+        assert_eq!(hi_lo(1), hi_lo(6));
+    }
+
+    #[test]
+    fn test_range_vs_range() {
+        use card::parse_cards;
+        use equity::{hand_vs_range, range_vs_range};
+        use range::Range;
+
+        let board = parse_cards("2c7d9hTsJs").unwrap();
+        let hero: Range = "AK".parse().unwrap();
+        let villain: Range = "QQ".parse().unwrap();
+        assert_eq!(Some(0.), range_vs_range(&hero, &villain, &board));
+        // one combo each is the same as hand against range
+        let hero: Range = "KK".parse().unwrap();
+        let villain: Range = "88, 22".parse().unwrap();
+        let one = hand_vs_range(&"KhKd".parse().unwrap(), &villain, &board);
+        assert_eq!(one, range_vs_range(&hero, &villain, &board));
+        assert_eq!(None, range_vs_range(&"22".parse().unwrap(), &"22".parse().unwrap(), &parse_cards("2c2d7h").unwrap()));
+    }
+
+    #[test]
+    fn test_omaha() {
+        use card::{parse_cards, CardRepr, OmahaHand};
+        use equity::omaha_vs_omaha;
+        use evaluate::evaluate_omaha;
+
+        let ids = |s: &str| parse_cards(s).unwrap().iter().map(|c| c.to_int()).collect::<Vec<_>>();
+        let rank = |hole: &str, board: &str| {
+            let h = ids(hole);
+            evaluate_omaha([h[0], h[1], h[2], h[3]], &ids(board))
+        };
+        // four hearts on board but only one in hand, no flush
+        assert!(rank("Ah2c3d4s", "KhQhJh9h5c") > 1599);
+        // trips on board plus a pair on board is no full house
+        let trips = rank("AsQd7s2h", "KsKcKd5h5d");
+        assert!((1610..=2467).contains(&trips));
+        assert!(rank("AcAd7s2h", "TcJdQhKs9c") > 1609);
+        assert_eq!(1600, rank("AcKd7s2h", "TcJdQh3s9c"));
+
+        let hero: OmahaHand = "AhKh7c6d".parse().unwrap();
+        let villain: OmahaHand = "QsQd2c3c".parse().unwrap();
+        let tally = omaha_vs_omaha(&hero, &villain, &parse_cards("Qh8h4s").unwrap());
+        assert_eq!(41 * 40 / 2, tally.total);
+        assert!((0.3..0.5).contains(&tally.equity()));
+    }
+
+    #[test]
+    fn test_hi_lo() {
+        use card::{parse_cards, OmahaHand};
+        use equity::{omaha8_vs_omaha8, split_shares};
+
+        // no low: the high hand scoops
+        assert_eq!(vec![1., 0.], split_shares(&[100, 200], &[None, None]));
+        // tied low, one player has the high too and gets three quarters
+        assert_eq!(vec![0.75, 0.25], split_shares(&[100, 200], &[Some(3), Some(3)]));
+        assert_eq!(vec![0.5, 0.5], split_shares(&[100, 200], &[None, Some(3)]));
+
+        let board = parse_cards("2c3d8hKsQs").unwrap();
+        let hero: OmahaHand = "AcAd4h5s".parse().unwrap();
+        let villain: OmahaHand = "KcKdAs4d".parse().unwrap();
+        // both make 8432A, villain's set of kings takes the high
+        let split = omaha8_vs_omaha8(&hero, &villain, &board);
+        assert_eq!(1, split.total);
+        assert_eq!(1, split.quarters[1]);
+        assert_eq!(0.25, split.equity());
+    }
+}
@@ -1,3 +1,10 @@
+use std::sync::OnceLock;
+
+use card::bit_to_id;
+use tables::{quinaries, Category};
+
+// NOFLUSH5, DP, FLUSH and SUITS are printed by `cargo run --bin gen_tables`
+
 const NOFLUSH5: [usize; 6175] = [
 	11,	167,	179,	23,	12,	1610,	2468,	1676,
 	24,	168,	2479,	2600,	180,	191,	1742,	192,
@@ -2441,7 +2448,7 @@ pub fn evaluate5(a:usize, b:usize, c:usize, d:usize, e:usize) -> usize {
 	suit_hash += SUITBIT_BY_ID[d];
 	suit_hash += SUITBIT_BY_ID[e];
 
-	if SUITS[suit_hash] == 1 {
+	if SUITS[suit_hash] != 0 {
 		suit_binary[a & 0x3] |= BIN_BY_ID[a];
 		suit_binary[b & 0x3] |= BIN_BY_ID[b];
 		suit_binary[c & 0x3] |= BIN_BY_ID[c];
@@ -2457,13 +2464,370 @@ pub fn evaluate5(a:usize, b:usize, c:usize, d:usize, e:usize) -> usize {
 	quinary[(d >> 2)]+=1;
 	quinary[(e >> 2)]+=1;
 
-	return NOFLUSH5[hash_quinary(quinary)];
+	return NOFLUSH5[hash_quinary(quinary, 5)];
+}
+
+/// Rank of the best five cards in a `to_52` mask of five to seven cards
+pub fn evaluate_mask(mask: u64) -> usize {
+	// one pass over the set bits, lowest first
+	let mut state = EvalState::new();
+	let mut rest = mask;
+	while rest != 0 {
+		state = state.add(bit_to_id(rest.trailing_zeros()));
+		rest &= rest - 1;
+	}
+	state.rank()
 }
 
+/// Hands per chunk in the batch evaluators. Each step runs over a whole
+/// chunk before the next, so the loops can become SIMD lanes.
+const LANES: usize = 8;
 
-fn hash_quinary(q: [usize; 13]) -> usize {
+/// `evaluate5` over many hands of card ids, writing one rank per hand
+pub fn evaluate5_batch(hands: &[[u8; 5]], ranks: &mut [u16]) {
+	assert_eq!(hands.len(), ranks.len());
+	for (hands, ranks) in hands.chunks(LANES).zip(ranks.chunks_mut(LANES)) {
+		let mut suit_hash = [0; LANES];
+		for (hash, h) in suit_hash.iter_mut().zip(hands) {
+			*hash = h.iter().map(|&x| SUITBIT_BY_ID[x as usize]).sum::<usize>();
+		}
+		let mut quinary = [[0; 13]; LANES];
+		for (q, h) in quinary.iter_mut().zip(hands) {
+			for &x in h {
+				q[x as usize >> 2] += 1;
+			}
+		}
+		for (i, rank) in ranks.iter_mut().enumerate() {
+			let suit = SUITS[suit_hash[i]];
+			*rank = if suit != 0 {
+				FLUSH[hands[i].iter().fold(0, |b, &x| b | BIN_BY_ID[x as usize])] as u16
+			} else {
+				NOFLUSH5[hash_quinary(quinary[i], 5)] as u16
+			};
+		}
+	}
+}
+
+/// `evaluate7` over many hands of card ids, writing one rank per hand
+pub fn evaluate7_batch(hands: &[[u8; 7]], ranks: &mut [u16]) {
+	assert_eq!(hands.len(), ranks.len());
+	let noflush = noflush7();
+	for (hands, ranks) in hands.chunks(LANES).zip(ranks.chunks_mut(LANES)) {
+		let mut suit_hash = [0; LANES];
+		for (hash, h) in suit_hash.iter_mut().zip(hands) {
+			*hash = h.iter().map(|&x| SUITBIT_BY_ID[x as usize]).sum::<usize>();
+		}
+		let mut quinary = [[0; 13]; LANES];
+		for (q, h) in quinary.iter_mut().zip(hands) {
+			for &x in h {
+				q[x as usize >> 2] += 1;
+			}
+		}
+		for (i, rank) in ranks.iter_mut().enumerate() {
+			let suit = SUITS[suit_hash[i]];
+			*rank = if suit != 0 {
+				let binary = hands[i].iter()
+					.filter(|&&x| x as usize & 0x3 == suit - 1)
+					.fold(0, |b, &x| b | BIN_BY_ID[x as usize]);
+				FLUSH[binary] as u16
+			} else {
+				noflush[hash_quinary(quinary[i], 7)] as u16
+			};
+		}
+	}
+}
+
+/// Category of an `evaluate5` or `evaluate7` rank
+pub fn category(rank: usize) -> Category {
+	use tables::Category::*;
+	match rank {
+		0..=10 => StraightFlush,
+		11..=166 => Quads,
+		167..=322 => FullHouse,
+		323..=1599 => Flush,
+		1600..=1609 => Straight,
+		1610..=2467 => Trips,
+		2468..=3325 => TwoPair,
+		3326..=6185 => Pair,
+		_ => HighCard,
+	}
+}
+
+/// Best five card rank out of seven cards
+pub fn evaluate7(a:usize, b:usize, c:usize, d:usize, e:usize, f:usize, g:usize) -> usize {
+	let mut suit_hash = 0;
+	let mut suit_binary = [0; 4];
+	let mut quinary = [0; 13];
+
+	for &x in &[a, b, c, d, e, f, g] {
+		suit_hash += SUITBIT_BY_ID[x];
+		suit_binary[x & 0x3] |= BIN_BY_ID[x];
+		quinary[x >> 2] += 1;
+	}
+
+	if SUITS[suit_hash] != 0 {
+		return FLUSH[suit_binary[SUITS[suit_hash]-1]];
+	}
+
+	noflush7()[hash_quinary(quinary, 7)]
+}
+
+/// Best hand made of exactly two of the four hole cards and three of the
+/// three to five board cards
+pub fn evaluate_omaha(hole: [usize; 4], board: &[usize]) -> usize {
+	// each board triple is hashed once and shared by the six hole pairs
+	let mut best = usize::MAX;
+	for x in 0..board.len() {
+		for y in x+1..board.len() {
+			for z in y+1..board.len() {
+				let triple = EvalState::new().add(board[x]).add(board[y]).add(board[z]);
+				for i in 0..3 {
+					for j in i+1..4 {
+						best = best.min(triple.add(hole[i]).add(hole[j]).rank());
+					}
+				}
+			}
+		}
+	}
+	best
+}
+
+/// Ace-to-five low: aces play low, straights and flushes don't count and
+/// pairs do. 1 is A2345 and 6175 is four kings.
+pub fn evaluate_ace_five(a:usize, b:usize, c:usize, d:usize, e:usize) -> usize {
+	let mut quinary = [0; 13];
+	for &x in &[a, b, c, d, e] {
+		quinary[x >> 2] += 1;
+	}
+	ace_five_table()[hash_quinary(quinary, 5)]
+}
+
+/// Short deck (6+) hold'em: A6789 is the lowest straight and flushes beat
+/// full houses. Ranks stay on the `evaluate5` scale with the flush and full
+/// house blocks swapped, so ranks only holding deuces to fives go unused.
+pub fn evaluate_short_deck(a:usize, b:usize, c:usize, d:usize, e:usize) -> usize {
+	// A6789 takes the place of the wheel
+	const A6789: usize = 1 << 12 | 1 << 7 | 1 << 6 | 1 << 5 | 1 << 4;
+	let ranks = 1 << (a >> 2) | 1 << (b >> 2) | 1 << (c >> 2) | 1 << (d >> 2) | 1 << (e >> 2);
+	if ranks == A6789 {
+		let suited = [b, c, d, e].iter().all(|&x| x & 0x3 == a & 0x3);
+		return if suited { 10 } else { 1609 };
+	}
+	match evaluate5(a, b, c, d, e) {
+		r @ 167..=322 => r + 1277,
+		r @ 323..=1599 => r - 156,
+		r => r,
+	}
+}
+
+/// Best short deck rank out of seven cards
+pub fn evaluate_short_deck7(cards: [usize; 7]) -> usize {
+	let mut best = usize::MAX;
+	for five in &FIVE_OF_SEVEN {
+		let [a, b, c, d, e] = *five;
+		best = best.min(evaluate_short_deck(cards[a], cards[b], cards[c], cards[d], cards[e]));
+	}
+	best
+}
+
+/// Deuce-to-seven low: aces play high and straights and flushes count
+/// against the hand, so the order is the high order reversed with A5432
+/// no straight. 1 is 75432 offsuit and 7462 a royal flush.
+pub fn evaluate_deuce_seven(a:usize, b:usize, c:usize, d:usize, e:usize) -> usize {
+	// A5432 is the lowest ace high, just behind A6432 on the `evaluate5`
+	// scale, and the hands between there and the wheel move up one
+	const A5432: usize = 1 << 12 | 1 << 3 | 1 << 2 | 1 << 1 | 1;
+	const A6432_FLUSH: usize = 815;
+	const A6432: usize = 6678;
+	let ranks = 1 << (a >> 2) | 1 << (b >> 2) | 1 << (c >> 2) | 1 << (d >> 2) | 1 << (e >> 2);
+	let high = if ranks == A5432 {
+		let suited = [b, c, d, e].iter().all(|&x| x & 0x3 == a & 0x3);
+		if suited { A6432_FLUSH } else { A6432 }
+	} else {
+		match evaluate5(a, b, c, d, e) {
+			r @ 11..=A6432_FLUSH => r - 1,
+			r @ 1610..=A6432 => r - 1,
+			r => r,
+		}
+	};
+	7463 - high
+}
+
+/// Indices of the 21 ways to pick five of seven cards
+const FIVE_OF_SEVEN: [[usize; 5]; 21] = [
+	[0, 1, 2, 3, 4], [0, 1, 2, 3, 5], [0, 1, 2, 3, 6], [0, 1, 2, 4, 5],
+	[0, 1, 2, 4, 6], [0, 1, 2, 5, 6], [0, 1, 3, 4, 5], [0, 1, 3, 4, 6],
+	[0, 1, 3, 5, 6], [0, 1, 4, 5, 6], [0, 2, 3, 4, 5], [0, 2, 3, 4, 6],
+	[0, 2, 3, 5, 6], [0, 2, 4, 5, 6], [0, 3, 4, 5, 6], [1, 2, 3, 4, 5],
+	[1, 2, 3, 4, 6], [1, 2, 3, 5, 6], [1, 2, 4, 5, 6], [1, 3, 4, 5, 6],
+	[2, 3, 4, 5, 6],
+];
+
+/// Ace-to-five ranks up to this one are eight or better: five different
+/// cards no higher than an eight
+pub const LOW8: usize = 56;
+
+/// Ace-to-five rank of a low that qualifies for half the pot
+pub fn evaluate_low8(a:usize, b:usize, c:usize, d:usize, e:usize) -> Option<usize> {
+	Some(evaluate_ace_five(a, b, c, d, e)).filter(|&r| r <= LOW8)
+}
+
+/// Best qualifying low out of seven cards, as in stud hi-lo
+pub fn evaluate_low8_7(cards: [usize; 7]) -> Option<usize> {
+	let mut best = None;
+	for five in &FIVE_OF_SEVEN {
+		let [a, b, c, d, e] = *five;
+		if let Some(r) = evaluate_low8(cards[a], cards[b], cards[c], cards[d], cards[e]) {
+			best = Some(best.map_or(r, |b: usize| b.min(r)));
+		}
+	}
+	best
+}
+
+/// Best qualifying low with two of the four hole cards and three board cards
+pub fn evaluate_omaha_low8(hole: [usize; 4], board: &[usize]) -> Option<usize> {
+	let mut best = None;
+	for x in 0..board.len() {
+		for y in x+1..board.len() {
+			for z in y+1..board.len() {
+				for i in 0..3 {
+					for j in i+1..4 {
+						if let Some(r) = evaluate_low8(hole[i], hole[j], board[x], board[y], board[z]) {
+							best = Some(best.map_or(r, |b: usize| b.min(r)));
+						}
+					}
+				}
+			}
+		}
+	}
+	best
+}
+
+/// Cards added one at a time into what `evaluate5` and `evaluate7` hash,
+/// so the turn and river can build on the flop's work
+#[derive(PartialEq, Eq, Debug, Default, Copy, Clone)]
+pub struct EvalState {
+	suit_hash: usize,
+	suit_binary: [usize; 4],
+	quinary: [usize; 13],
+	cards: usize,
+}
+
+impl EvalState {
+	pub fn new() -> Self {
+		EvalState::default()
+	}
+
+	/// The state with one more card id
+	pub fn add(&self, id: usize) -> Self {
+		let mut ret = *self;
+		ret.suit_hash += SUITBIT_BY_ID[id];
+		ret.suit_binary[id & 0x3] |= BIN_BY_ID[id];
+		ret.quinary[id >> 2] += 1;
+		ret.cards += 1;
+		ret
+	}
+
+	pub fn cards(&self) -> usize {
+		self.cards
+	}
+
+	/// Rank of the best five cards. Only five to seven cards have tables,
+	/// so anything else is a bug in the caller and panics.
+	pub fn rank(&self) -> usize {
+		let suit = SUITS[self.suit_hash];
+		if suit != 0 {
+			return FLUSH[self.suit_binary[suit - 1]];
+		}
+		match self.cards {
+			5 => NOFLUSH5[hash_quinary(self.quinary, 5)],
+			6 => noflush6()[hash_quinary(self.quinary, 6)],
+			7 => noflush7()[hash_quinary(self.quinary, 7)],
+			n => panic!("can't rank {} cards", n),
+		}
+	}
+}
+
+/// Same as `NOFLUSH5` for six and seven cards, built on first use and
+/// shared by every thread
+static NOFLUSH6: OnceLock<Vec<usize>> = OnceLock::new();
+static NOFLUSH7: OnceLock<Vec<usize>> = OnceLock::new();
+static ACE_FIVE: OnceLock<Vec<usize>> = OnceLock::new();
+
+fn noflush6() -> &'static [usize] {
+	NOFLUSH6.get_or_init(|| noflush(6))
+}
+
+fn noflush7() -> &'static [usize] {
+	NOFLUSH7.get_or_init(|| noflush(7))
+}
+
+fn ace_five_table() -> &'static [usize] {
+	ACE_FIVE.get_or_init(ace_five)
+}
+
+/// Builds the tables up front, e.g. before starting threads that would
+/// otherwise wait on the first one to need them
+pub fn init() {
+	noflush6();
+	noflush7();
+	ace_five_table();
+}
+
+/// Rank of the best five cards of every `k` card quinary without a flush
+fn noflush(k: usize) -> Vec<usize> {
+	fn best5(q: &[usize; 13], sub: &mut [usize; 13], i: usize, left: usize) -> usize {
+		if left == 0 {
+			return NOFLUSH5[hash_quinary(*sub, 5)];
+		}
+		if i == 13 {
+			return usize::MAX;
+		}
+		let mut best = usize::MAX;
+		for n in 0..=left.min(q[i]) {
+			sub[i] = n;
+			best = best.min(best5(q, sub, i + 1, left - n));
+		}
+		sub[i] = 0;
+		best
+	}
+
+	let mut table = Vec::new();
+	quinaries(&mut [0; 13], 0, k, &mut |q| {
+		let hash = hash_quinary(*q, k);
+		if hash >= table.len() {
+			table.resize(hash + 1, 0);
+		}
+		table[hash] = best5(q, &mut [0; 13], 0, 5);
+	});
+	table
+}
+
+/// Ace-to-five low rank of every five card quinary, 1 for A2345
+fn ace_five() -> Vec<usize> {
+	let mut hands = Vec::new();
+	quinaries(&mut [0; 13], 0, 5, &mut |q| hands.push(*q));
+	// fewer matched cards first, then the lowest top card, aces low
+	hands.sort_by_key(|q| {
+		let mut groups: Vec<(usize, usize)> = (0..13)
+			.filter(|&i| q[i] > 0)
+			.map(|i| (q[i], (i + 1) % 13))
+			.collect();
+		groups.sort_by(|a, b| b.cmp(a));
+		let counts: Vec<usize> = groups.iter().map(|g| g.0).collect();
+		let ranks: Vec<usize> = groups.iter().map(|g| g.1).collect();
+		(counts, ranks)
+	});
+	let mut table = vec![0; hands.len()];
+	for (i, q) in hands.iter().enumerate() {
+		table[hash_quinary(*q, 5)] = i + 1;
+	}
+	table
+}
+
+fn hash_quinary(q: [usize; 13], k: usize) -> usize {
 	let mut sum = 0;
-    let mut k = 5;
+	let mut k = k;
 	for i in 0..=13 {
 		sum += DP[q[i]][13-i-1][k];
 		k -= q[i];
@@ -2474,3 +2838,139 @@ fn hash_quinary(q: [usize; 13]) -> usize {
 	}
 	return sum;
 }
+
+mod tests {
+	#[test]
+	fn test_generated_tables() {
+		use tables;
+		use super::{DP, FLUSH, NOFLUSH5, SUITS};
+
+		assert_eq!(DP, tables::dp());
+		assert_eq!(&NOFLUSH5[..], &tables::noflush5()[..]);
+		assert_eq!(&FLUSH[..], &tables::flush()[..]);
+		assert_eq!(&SUITS[..], &tables::suits()[..]);
+	}
+
+	#[test]
+	fn test_all_five_card_hands() {
+		use std::collections::HashMap;
+		use tables::Category;
+		use super::{category, evaluate5};
+
+		/// Category and ranks that break ties, read straight off the cards and
+		/// packed so that better hands compare higher
+		fn naive_value(cards: [usize; 5]) -> usize {
+			let mut counts = [0; 13];
+			for &c in &cards {
+				counts[c >> 2] += 1;
+			}
+			// ranks by how many of them, then by rank, high first
+			let mut groups = Vec::new();
+			for n in (1..5).rev() {
+				for r in (0..13).rev() {
+					if counts[r] == n {
+						groups.push(r);
+					}
+				}
+			}
+			let flush = cards.iter().all(|&c| c & 0x3 == cards[0] & 0x3);
+			let straight = match groups.len() {
+				5 if groups[0] - groups[4] == 4 => Some(groups[0]),
+				5 if groups == [12, 3, 2, 1, 0] => Some(3),
+				_ => None,
+			};
+			let most = counts.iter().max().unwrap();
+			let category = match (straight, flush, most, groups.len()) {
+				(Some(_), true, _, _) => 8,
+				(_, _, 4, _) => 7,
+				(_, _, 3, 2) => 6,
+				(_, true, _, _) => 5,
+				(Some(_), _, _, _) => 4,
+				(_, _, 3, _) => 3,
+				(_, _, 2, 3) => 2,
+				(_, _, 2, _) => 1,
+				_ => 0,
+			};
+			if let Some(high) = straight {
+				groups = vec![high];
+			}
+			(0..5).fold(category, |v, i| v * 16 + groups.get(i).unwrap_or(&0))
+		}
+
+		// every hand against `naive_value`, which shares no code with the tables
+		let mut ranks = HashMap::new();
+		let mut counts = [0; 9];
+		let mut hands = 0;
+		for a in 0..52 {
+			for b in 0..a {
+				for c in 0..b {
+					for d in 0..c {
+						for e in 0..d {
+							let rank = evaluate5(a, b, c, d, e);
+							let value = naive_value([a, b, c, d, e]);
+							assert_eq!(rank, *ranks.entry(value).or_insert(rank));
+							assert_eq!(value >> 20, category(rank) as usize);
+							counts[category(rank) as usize] += 1;
+							hands += 1;
+						}
+					}
+				}
+			}
+		}
+		// equal hands share a rank and better hands rank lower
+		let mut values: Vec<(usize, usize)> = ranks.into_iter().collect();
+		values.sort();
+		assert!(values.iter().map(|v| v.1).eq((1..=7462).rev()));
+		assert_eq!(2598960, hands);
+		assert_eq!(1302540, counts[Category::HighCard as usize]);
+		assert_eq!(1098240, counts[Category::Pair as usize]);
+		assert_eq!(123552, counts[Category::TwoPair as usize]);
+		assert_eq!(54912, counts[Category::Trips as usize]);
+		assert_eq!(10200, counts[Category::Straight as usize]);
+		assert_eq!(5108, counts[Category::Flush as usize]);
+		assert_eq!(3744, counts[Category::FullHouse as usize]);
+		assert_eq!(624, counts[Category::Quads as usize]);
+		assert_eq!(40, counts[Category::StraightFlush as usize]);
+	}
+	#[test]
+	fn test_batch() {
+		use super::{evaluate5, evaluate5_batch, evaluate7, evaluate7_batch};
+		use rand::{thread_rng, Rng};
+
+		let mut deck: Vec<u8> = (0..52).collect();
+		let mut hands = vec![[48, 44, 40, 36, 32, 1, 2], [49, 45, 41, 5, 33, 9, 13]];
+		for _ in 0..1000 {
+			thread_rng().shuffle(&mut deck);
+			hands.push([deck[0], deck[1], deck[2], deck[3], deck[4], deck[5], deck[6]]);
+		}
+		let fives: Vec<[u8; 5]> = hands.iter().map(|h| [h[0], h[1], h[2], h[3], h[4]]).collect();
+		let mut ranks = vec![0; hands.len()];
+		evaluate5_batch(&fives, &mut ranks);
+		for (h, &r) in fives.iter().zip(&ranks) {
+			let h: Vec<usize> = h.iter().map(|&x| x as usize).collect();
+			assert_eq!(evaluate5(h[0], h[1], h[2], h[3], h[4]), r as usize);
+		}
+		evaluate7_batch(&hands, &mut ranks);
+		for (h, &r) in hands.iter().zip(&ranks) {
+			let h: Vec<usize> = h.iter().map(|&x| x as usize).collect();
+			assert_eq!(evaluate7(h[0], h[1], h[2], h[3], h[4], h[5], h[6]), r as usize);
+		}
+	}
+	#[test]
+	fn test_eval_state() {
+		use super::{evaluate5, evaluate7, EvalState};
+
+		// royal flush then a paired board without a flush
+		let flop = [48, 44, 40].iter().fold(EvalState::new(), |s, &x| s.add(x));
+		let five = flop.add(36).add(32);
+		assert_eq!(1, five.rank());
+		assert_eq!(1, five.add(0).rank());
+		assert_eq!(evaluate7(48, 44, 40, 36, 32, 0, 1), five.add(0).add(1).rank());
+		let pairs = [49, 45, 41, 1, 2].iter().fold(EvalState::new(), |s, &x| s.add(x));
+		assert_eq!(evaluate5(49, 45, 41, 1, 2), pairs.rank());
+		// kings and deuces with an ace out of six
+		assert_eq!(evaluate5(49, 45, 46, 1, 2), pairs.add(46).rank());
+		assert_eq!(evaluate7(49, 45, 41, 1, 2, 46, 3), pairs.add(46).add(3).rank());
+		assert_eq!(7, pairs.add(46).add(3).cards());
+	}
+}
@@ -0,0 +1,424 @@
+//! Reads hand histories in the PokerStars text format, which most sites
+//! and trackers can also export, into the decisions hero made.
+
+use card::{parse_cards, Card, Hand};
+use odds::parse_number;
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Street {
+    Preflop,
+    Flop,
+    Turn,
+    River,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Eq, Debug, Copy, Clone)]
+#[serde(rename_all = "lowercase")]
+pub enum Action {
+    Fold,
+    Check,
+    Call,
+    Bet,
+    Raise,
+}
+
+impl Street {
+    /// The name used in session JSON and CSV, e.g. "turn"
+    pub fn to_text(&self) -> &'static str {
+        match self {
+            Street::Preflop => "preflop",
+            Street::Flop => "flop",
+            Street::Turn => "turn",
+            Street::River => "river",
+        }
+    }
+}
+
+impl Action {
+    /// The name used in session JSON and CSV, e.g. "call"
+    pub fn to_text(&self) -> &'static str {
+        match self {
+            Action::Fold => "fold",
+            Action::Check => "check",
+            Action::Call => "call",
+            Action::Bet => "bet",
+            Action::Raise => "raise",
+        }
+    }
+}
+
+/// One time hero had to act
+#[derive(PartialEq, Debug, Clone)]
+pub struct Decision {
+    pub street: Street,
+    pub board: Vec<Card>,
+    /// Everything in the middle, the bet hero faces included
+    pub pot: f32,
+    pub to_call: f32,
+    pub action: Action,
+    /// Who made the bet hero faces, the big blind for a limp
+    pub bettor: Option<String>,
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct HandHistory {
+    pub id: String,
+    pub hero_name: String,
+    pub hero: Hand,
+    pub big_blind: f32,
+    pub board: Vec<Card>,
+    pub decisions: Vec<Decision>,
+    /// Hands the other players showed down, by name
+    pub shown: Vec<(String, Hand)>,
+}
+
+impl HandHistory {
+    /// What the player who bet into `decision` showed down, if anything
+    pub fn bettor_shown(&self, decision: &Decision) -> Option<Hand> {
+        let bettor = decision.bettor.as_ref()?;
+        self.shown.iter().find(|s| &s.0 == bettor).map(|s| s.1)
+    }
+}
+
+/// Whether `line` starts a hand, as in "PokerStars Hand #", "PokerStars
+/// Zoom Hand #" or the older "PokerStars Game #"
+fn is_header(line: &str) -> bool {
+    let rest = match line.strip_prefix("PokerStars ") {
+        Some(rest) => rest,
+        None => return false,
+    };
+    match rest.find(" #") {
+        Some(i) => {
+            let kind = &rest[..i];
+            let id = &rest[i + 2..];
+            (kind.ends_with("Hand") || kind.ends_with("Game")) && id.starts_with(|c: char| c.is_ascii_digit())
+        }
+        None => false,
+    }
+}
+
+/// Reads every hand in `text`, hands starting at their header line, see
+/// `is_header`. Text before the first header, like a tracker's export
+/// notes, is skipped and so are hands hero wasn't dealt into. A hand that
+/// can't be read is left out too, its error returned with its header.
+pub fn parse(text: &str) -> (Vec<HandHistory>, Vec<String>) {
+    let (mut hands, mut errors) = (Vec::new(), Vec::new());
+    let mut read = |lines: &[&str]| match parse_hand(lines) {
+        Ok(hand) => hands.extend(hand),
+        Err(e) => errors.push(format!("{}: {}", lines[0], e)),
+    };
+    let mut lines: Vec<&str> = Vec::new();
+    for line in text.lines().map(|l| l.trim()) {
+        if is_header(line) && !lines.is_empty() {
+            read(&lines);
+            lines.clear();
+        }
+        if !line.is_empty() && (is_header(line) || !lines.is_empty()) {
+            lines.push(line);
+        }
+    }
+    if !lines.is_empty() {
+        read(&lines);
+    }
+    (hands, errors)
+}
+
+/// The cards between the last pair of brackets in `line`
+fn bracketed(line: &str) -> Result<Vec<Card>, String> {
+    match (line.rfind('['), line.rfind(']')) {
+        (Some(i), Some(j)) if i < j => parse_cards(&line[i + 1..j]),
+        _ => Err(format!("no cards in: {}", line)),
+    }
+}
+
+fn amount(s: &str) -> Result<f32, String> {
+    parse_number(s).ok_or_else(|| format!("bad amount: {}", s))
+}
+
+/// The first amount in `what`, e.g. $0.05 in "posts small blind $0.05 and
+/// is all-in"
+fn first_amount(what: &str) -> Result<f32, String> {
+    let token = what.split_whitespace().find(|t| t.contains(|c: char| c.is_ascii_digit()));
+    amount(token.unwrap_or(what))
+}
+
+fn parse_hand(lines: &[&str]) -> Result<Option<HandHistory>, String> {
+    let header = lines[0];
+    let id = header.split(&['#', ':'][..]).nth(1).unwrap_or("").to_string();
+    // stakes as "($0.05/$0.10 USD)"
+    let big_blind = header.find('(')
+        .and_then(|i| header[i + 1..].split(&['/', ')'][..]).nth(1))
+        .and_then(parse_number)
+        .ok_or_else(|| format!("no stakes in: {}", header))?;
+
+    let mut hero = None;
+    let mut board = Vec::new();
+    let mut street = Street::Preflop;
+    let mut pot = 0.;
+    // what each player has put in on this street
+    let mut committed: Vec<(String, f32)> = Vec::new();
+    // who put in the most on this street
+    let mut bettor: Option<String> = None;
+    let mut decisions = Vec::new();
+    let mut shown = Vec::new();
+
+    for &line in &lines[1..] {
+        if let Some(rest) = line.strip_prefix("Dealt to ") {
+            let name = rest[..rest.rfind('[').unwrap_or(rest.len())].trim();
+            let cards = bracketed(line)?;
+            if cards.len() != 2 {
+                return Err(format!("not hold'em: {}", line));
+            }
+            hero = Some((name.to_string(), Hand([cards[0], cards[1]])));
+            continue;
+        }
+        if line.starts_with("*** ") {
+            let next = if line.starts_with("*** FLOP") {
+                Street::Flop
+            } else if line.starts_with("*** TURN") {
+                Street::Turn
+            } else if line.starts_with("*** RIVER") {
+                Street::River
+            } else if line.starts_with("*** SUMMARY") {
+                break;
+            } else {
+                continue;
+            };
+            street = next;
+            let cards = bracketed(line)?;
+            if street == Street::Flop {
+                board = cards;
+            } else {
+                board.extend(cards);
+            }
+            committed.clear();
+            bettor = None;
+            continue;
+        }
+        if line.starts_with("Uncalled bet") {
+            pot -= amount(&line[..line.find(')').unwrap_or(line.len())])?;
+            continue;
+        }
+
+        let i = match line.find(": ") {
+            Some(i) => i,
+            None => continue,
+        };
+        let (name, what) = (&line[..i], &line[i + 2..]);
+        if what.starts_with("shows [") {
+            let cards = bracketed(what)?;
+            if cards.len() == 2 && hero.as_ref().map(|h| h.0.as_str()) != Some(name) {
+                shown.push((name.to_string(), Hand([cards[0], cards[1]])));
+            }
+            continue;
+        }
+
+        let max = committed.iter().map(|c| c.1).fold(0., f32::max);
+        let before = committed.iter().find(|c| c.0 == name).map_or(0., |c| c.1);
+        let (action, total) = if what.starts_with("folds") {
+            (Some(Action::Fold), before)
+        } else if what.starts_with("checks") {
+            (Some(Action::Check), before)
+        } else if what.starts_with("calls ") {
+            (Some(Action::Call), before + amount(what.split_whitespace().nth(1).unwrap_or(""))?)
+        } else if what.starts_with("bets ") {
+            (Some(Action::Bet), before + amount(what.split_whitespace().nth(1).unwrap_or(""))?)
+        } else if what.starts_with("raises ") {
+            // "raises $0.20 to $0.30"
+            let to = what.rsplit(" to ").next().unwrap_or("");
+            (Some(Action::Raise), amount(to.split_whitespace().next().unwrap_or(""))?)
+        } else if what.starts_with("posts the ante") {
+            pot += first_amount(what)?;
+            (None, before)
+        } else if what.starts_with("posts ") {
+            (None, before + first_amount(what)?)
+        } else {
+            continue;
+        };
+
+        if let (Some(action), Some(hero)) = (action, hero.as_ref()) {
+            if hero.0 == name {
+                decisions.push(Decision {
+                    street,
+                    board: board.clone(),
+                    pot,
+                    to_call: max - before,
+                    action,
+                    bettor: bettor.clone(),
+                });
+            }
+        }
+        if total > max {
+            bettor = Some(name.to_string());
+        }
+        pot += total - before;
+        match committed.iter_mut().find(|c| c.0 == name) {
+            Some(c) => c.1 = total,
+            None => committed.push((name.to_string(), total)),
+        }
+    }
+
+    Ok(hero.map(|(hero_name, hero)| HandHistory {
+        id,
+        hero_name,
+        hero,
+        big_blind,
+        board,
+        decisions,
+        shown,
+    }))
+}
+
+mod tests {
+    #[test]
+    fn test_parse_pokerstars() {
+        use history::*;
+
+        let text = "
+PokerStars Hand #200000000001:  Hold'em No Limit ($0.05/$0.10 USD) - 2019/05/01 20:00:00 ET
+Table 'Alpha' 6-max Seat #1 is the button
+Seat 1: Villain ($10 in chips)
+Seat 2: Hero ($10 in chips)
+Villain: posts small blind $0.05
+Hero: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+Villain: raises $0.20 to $0.30
+Hero: calls $0.20
+*** FLOP *** [2h 7h 9c]
+Hero: checks
+Villain: bets $0.40
+Hero: calls $0.40
+*** TURN *** [2h 7h 9c] [Ts]
+Hero: checks
+Villain: bets $1.50
+Hero: folds
+Uncalled bet ($1.50) returned to Villain
+Villain collected $1.40 from pot
+*** SUMMARY ***
+Total pot $1.40 | Rake $0
+";
+        let (hands, errors) = parse(text);
+        assert!(errors.is_empty());
+        assert_eq!(1, hands.len());
+        let hand = &hands[0];
+        assert_eq!("200000000001", hand.id);
+        assert_eq!("Hero", hand.hero_name);
+        assert_eq!(0.1, hand.big_blind);
+        assert_eq!(4, hand.board.len());
+        assert_eq!(5, hand.decisions.len());
+
+        let preflop = &hand.decisions[0];
+        assert_eq!((Street::Preflop, Action::Call), (preflop.street, preflop.action));
+        assert!((preflop.pot - 0.4).abs() < 1e-6 && (preflop.to_call - 0.2).abs() < 1e-6);
+        let flop = &hand.decisions[2];
+        assert_eq!((Street::Flop, Action::Call), (flop.street, flop.action));
+        assert!((flop.pot - 1.).abs() < 1e-6 && (flop.to_call - 0.4).abs() < 1e-6);
+        let turn = &hand.decisions[4];
+        assert_eq!(Action::Fold, turn.action);
+        assert_eq!(4, turn.board.len());
+        assert!((turn.pot - 2.9).abs() < 1e-5 && (turn.to_call - 1.5).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_headers_and_bettor() {
+        use history::*;
+
+        let text = "
+Exported by a tracker, 2 hands
+
+PokerStars Zoom Hand #200000000002:  Hold'em No Limit ($0.05/$0.10) - 2019/05/01 20:00:00 ET
+Seat 1: Villain ($10 in chips)
+Seat 2: Hero ($10 in chips)
+Seat 3: Other ($10 in chips)
+Villain: posts small blind $0.05
+Hero: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+Other: calls $0.10
+Villain: calls $0.05
+Hero: checks
+*** FLOP *** [2h 7h 9c]
+Villain: checks
+Hero: checks
+Other: bets $0.20
+Villain: calls $0.20
+Hero: calls $0.20
+*** TURN *** [2h 7h 9c] [Ts]
+Villain: checks
+Hero: checks
+Other: checks
+*** RIVER *** [2h 7h 9c Ts] [Js]
+Villain: checks
+Hero: checks
+Other: checks
+*** SHOW DOWN ***
+Villain: shows [8s 8c] (a straight, Seven to Jack)
+Hero: shows [Ah Kh] (high card Ace)
+Other: shows [Qd Qc] (a pair of Queens)
+*** SUMMARY ***
+PokerStars Game #27853:  Hold'em No Limit ($1/$2) - 2009/05/01 20:00:00 ET
+Seat 1: Hero ($200 in chips)
+Seat 2: Villain ($200 in chips)
+Hero: posts small blind $1
+Villain: posts big blind $2
+*** HOLE CARDS ***
+Dealt to Hero [2c 2d]
+Hero: calls $1
+Villain: checks
+*** SUMMARY ***
+";
+        let (hands, errors) = parse(text);
+        assert!(errors.is_empty());
+        assert_eq!(2, hands.len());
+        assert_eq!("200000000002", hands[0].id);
+        assert_eq!("27853", hands[1].id);
+        assert_eq!(2., hands[1].big_blind);
+
+        // the flop bet came from Other, not the first hand shown
+        let flop = &hands[0].decisions[2];
+        assert_eq!((Street::Flop, Action::Call), (flop.street, flop.action));
+        assert_eq!(Some("Other".to_string()), flop.bettor);
+        assert_eq!(Some("QdQc".parse().unwrap()), hands[0].bettor_shown(flop));
+        assert_eq!(Some("Villain".to_string()), hands[1].decisions[0].bettor);
+        assert_eq!(None, hands[1].bettor_shown(&hands[1].decisions[0]));
+    }
+
+    #[test]
+    fn test_all_in_blind() {
+        use history::*;
+
+        let text = "
+PokerStars Hand #200000000003:  Hold'em No Limit ($0.05/$0.10 USD) - 2019/05/01 20:00:00 ET
+Seat 1: Villain ($0.05 in chips)
+Seat 2: Hero ($10 in chips)
+Villain: posts small blind $0.05 and is all-in
+Hero: posts big blind $0.10
+*** HOLE CARDS ***
+Dealt to Hero [Ah Kh]
+Uncalled bet ($0.05) returned to Hero
+*** FLOP *** [2h 7h 9c]
+*** TURN *** [2h 7h 9c] [Ts]
+*** RIVER *** [2h 7h 9c Ts] [Js]
+*** SUMMARY ***
+PokerStars Hand #200000000004:  Omaha Pot Limit ($0.05/$0.10 USD) - 2019/05/01 20:01:00 ET
+Hero: posts small blind $0.05
+Dealt to Hero [Ah Kh Qd Jd]
+*** SUMMARY ***
+PokerStars Hand #200000000005:  Hold'em No Limit ($0.05/$0.10 USD) - 2019/05/01 20:02:00 ET
+Hero: posts small blind $0.05
+Villain: posts small & big blinds $0.15
+Dealt to Hero [2c 2d]
+Hero: folds
+*** SUMMARY ***
+";
+        // the Omaha hand is reported without losing the others
+        let (hands, errors) = parse(text);
+        assert_eq!(vec!["200000000003", "200000000005"], hands.iter().map(|h| h.id.as_str()).collect::<Vec<_>>());
+        assert_eq!(1, errors.len());
+        assert!(errors[0].contains("#200000000004") && errors[0].contains("not hold'em"));
+        assert_eq!(5, hands[0].board.len());
+        assert!((hands[1].decisions[0].to_call - 0.1).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,127 @@
+//! Suit isomorphism: deals that only differ by relabeling suits play the
+//! same, so each class is represented by one deal and the number of deals
+//! it stands for.
+
+use std::sync::OnceLock;
+
+use card::{Card, CardRepr, Flop, Hand, Suit};
+
+/// The 24 ways to relabel the four suits, `perm[old] = new`
+fn permutations() -> Vec<[u64; 4]> {
+    (0..256)
+        .map(|i| [i & 3, i >> 2 & 3, i >> 4 & 3, i >> 6 & 3])
+        .filter(|perm| (0..4).all(|s| perm.contains(&s)))
+        .collect()
+}
+
+fn relabel(cards: &[Card], perm: &[u64; 4]) -> Vec<Card> {
+    let mut ret: Vec<Card> = cards.iter()
+        .map(|c| Card(Suit::get(perm[c.0.value() as usize]), c.1))
+        .collect();
+    // highest rank first, ties by suit
+    ret.sort_by_key(|c| (!c.1.value(), c.0.value()));
+    ret
+}
+
+fn ids(cards: &[Card]) -> Vec<usize> {
+    cards.iter().map(|c| c.to_int()).collect()
+}
+
+/// Representative and multiplicity of card groups that keep their identity,
+/// e.g. hole cards and the board.
+///
+/// The representative puts the lowest suits on the highest cards, so "AsKs"
+/// becomes "AcKc". The multiplicity counts the distinct deals that map to it.
+pub fn canonical_groups(groups: &[&[Card]]) -> (Vec<Vec<Card>>, usize) {
+    let mut images: Vec<Vec<Vec<Card>>> = permutations().iter()
+        .map(|perm| groups.iter().map(|g| relabel(g, perm)).collect())
+        .collect();
+    images.sort_by_key(|image: &Vec<Vec<Card>>| image.iter().map(|g| ids(g)).collect::<Vec<_>>());
+    images.dedup();
+    let multiplicity = images.len();
+    (images.swap_remove(0), multiplicity)
+}
+
+/// Representative of a set of cards, highest card first, and how many
+/// sets share it
+pub fn canonical(cards: &[Card]) -> (Vec<Card>, usize) {
+    let (mut groups, multiplicity) = canonical_groups(&[cards]);
+    (groups.swap_remove(0), multiplicity)
+}
+
+pub fn canonical_hand(hand: &Hand) -> (Hand, usize) {
+    let (cards, multiplicity) = canonical(&hand.0);
+    (Hand([cards[0], cards[1]]), multiplicity)
+}
+
+pub fn canonical_flop(flop: &Flop) -> (Flop, usize) {
+    let (cards, multiplicity) = canonical(&flop.0);
+    (Flop([cards[0], cards[1], cards[2]]), multiplicity)
+}
+
+/// Hole cards and board relabeled together. The flop is a set but the
+/// turn and river keep their places, so runouts in another order stay
+/// apart.
+pub fn canonical_with_board(hand: &Hand, board: &[Card]) -> (Hand, Vec<Card>, usize) {
+    let flop = board.len().min(3);
+    let mut groups: Vec<&[Card]> = vec![&hand.0, &board[..flop]];
+    groups.extend(board[flop..].chunks(1));
+    let (groups, multiplicity) = canonical_groups(&groups);
+    let hand = Hand([groups[0][0], groups[0][1]]);
+    (hand, groups[1..].concat(), multiplicity)
+}
+
+static FLOPS: OnceLock<Vec<(Flop, usize)>> = OnceLock::new();
+
+/// Every strategically distinct flop with its multiplicity, worked out on
+/// first use
+pub fn flops() -> &'static [(Flop, usize)] {
+    FLOPS.get_or_init(|| {
+        let mut ret = Vec::new();
+        for a in 0..52 {
+            for b in 0..a {
+                for c in 0..b {
+                    let cards = [Card::from_int(a), Card::from_int(b), Card::from_int(c)];
+                    let (canonical, multiplicity) = canonical_flop(&Flop(cards));
+                    if canonical.0[..] == relabel(&cards, &[0, 1, 2, 3])[..] {
+                        ret.push((canonical, multiplicity));
+                    }
+                }
+            }
+        }
+        ret
+    })
+}
+
+mod tests {
+    #[test]
+    fn test_canonical() {
+        use card::{parse_cards, Hand};
+        use iso::*;
+
+        let hand: Hand = "7s6s".parse().unwrap();
+        assert_eq!(("7c6c".parse().unwrap(), 4), canonical_hand(&hand));
+        assert_eq!(("AcAd".parse().unwrap(), 6), canonical_hand(&"AhAs".parse().unwrap()));
+        assert_eq!(("KcQd".parse().unwrap(), 12), canonical_hand(&"QsKh".parse().unwrap()));
+
+        let flops = flops();
+        assert_eq!(1755, flops.len());
+        assert_eq!(22100, flops.iter().map(|f| f.1).sum::<usize>());
+
+        // the board pins the suits so the hand keeps its relation to it
+        let board = parse_cards("Ah7d2c").unwrap();
+        let (hand, board, n) = canonical_with_board(&"KhQh".parse().unwrap(), &board);
+        assert_eq!("KcQc".parse::<Hand>().unwrap(), hand);
+        assert_eq!(parse_cards("Ac7d2h").unwrap(), board);
+        assert_eq!(24, n);
+        // monotone board with a hand of none of its suits
+        let (_, _, n) = canonical_with_board(&"AsKs".parse().unwrap(), &parse_cards("2h3h4h").unwrap());
+        assert_eq!(12, n);
+        // the same cards with the turn and river swapped are another runout
+        let hand = "AhKh".parse().unwrap();
+        let one = canonical_with_board(&hand, &parse_cards("2c7d9hTsJs").unwrap());
+        let other = canonical_with_board(&hand, &parse_cards("2c7d9hJsTs").unwrap());
+        assert_ne!(one.1, other.1);
+        assert_eq!(one.1[..3], other.1[..3]);
+    }
+}
@@ -0,0 +1,26 @@
+extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
+extern crate toml;
+
+pub mod card;
+pub mod config;
+pub mod deck;
+pub mod drills;
+pub mod engine;
+pub mod equity;
+pub mod evaluate;
+pub mod history;
+pub mod iso;
+pub mod odds;
+pub mod packs;
+pub mod pot;
+pub mod preflop;
+pub mod quiz;
+pub mod rake;
+pub mod range;
+pub mod session;
+pub mod tables;
+pub mod texture;
@@ -1,217 +1,75 @@
 #![feature(custom_attribute)]
-#![feature(vec_remove_item)]
 
 extern crate rand;
 #[macro_use]
 extern crate stdweb;
+extern crate poker_trainer;
 
 use rand::prelude::*;
 use stdweb::web::event::ClickEvent;
 use stdweb::web::{document, IParentNode, IEventTarget};
+use stdweb::unstable::TryInto;
 
-const SIZES : [f32; 10] = [
-    100.,
-    150.,
-    200.,
-    300.,
-    400.,
-    500.,
-    800.,
-    1000.,
-    1200.,
-    2000.,
-];
+use poker_trainer::{card, config, deck, drills, engine, equity, evaluate, history, odds, packs, pot, preflop, quiz, rake, range, session, tables, texture};
+use card::{Card, CardRepr, Flop, Hand, OmahaHand};
+use config::Config;
+use deck::Deck;
+use drills::Drill;
+use engine::{Game, Logged, BUTTON};
+use equity::{EquityDistribution, Split};
+use history::{Action, HandHistory, Street};
+use packs::Pack;
+use pot::Pot;
+use quiz::{Ask, Question, Quiz, Score};
+use rake::Rake;
+use range::{HandClass, Range};
+use session::{DecisionRecord, ScenarioRecord, Session};
+use tables::Category;
+use texture::{Change, Suits, Texture};
 
-const BETS: [f32; 10] = [
-    0.08,
-    0.1,
-    0.15,
-    0.175,
-    0.18,
-    0.2,
-    0.5,
-    0.75,
-    0.8,
-    1.,
+/// Villain ranges for preflop spots
+const SHOVE_RANGES: [&str; 4] = [
+    "22+, A2s+, K9s+, QTs+, JTs, A8o+, KJo+",
+    "55+, A7s+, KTs+, QJs, ATo+, KQo",
+    "77+, A9s+, KQs, AJo+",
+    "TT+, AQs+, AKo",
 ];
 
-pub mod card;
-pub mod evaluate;
-use card::{Card, Suit, Rank, Flop, Hand};
-
-pub struct Deck {
-    deck: Vec<Card>,
-}
-
-impl Deck {
-    pub fn new() -> Self {
-        let mut deck = Vec::new();
-        for i in 0..13 {
-            for j in 0..4 {
-                let suit = Suit::get(j);
-                let num = Rank::get(i);
-                deck.push(Card(suit, num));
-            }
-        }
-
-        Self {
-            deck,
-        }
-    }
-
-    pub fn remove_card(&mut self, card: &Card) -> bool {
-        self.deck.remove_item(card).is_some()
-    }
-
-    pub fn _deal_one_random(&mut self) -> Option<Card> {
-        let i : usize = random::<usize>() % self.deck.len() as usize;
-        Some(self.deck[i])
-    }
-
-    pub fn deal_one_rand(&mut self) -> Option<Card> {
-        let card = self._deal_one_random()?;
-        self.remove_card(&card);
-        Some(card)
-    }
-
-    pub fn deal_suit(&mut self, suit: Suit) -> Option<Card> {
-        let mut card = self._deal_one_random();
-        while card?.0 != suit {
-            card = self._deal_one_random();
-        }
-        self.remove_card(&card?);
-        card
-    }
-
-    pub fn deal_rank(&mut self, rank: Rank) -> Option<Card> {
-        let mut card = self._deal_one_random();
-        while card?.1 != rank {
-            card = self._deal_one_random();
-        }
-        self.remove_card(&card?);
-        card
-    }
-
-    pub fn flush_draw(&mut self) -> Option<(Flop, Hand, Card)> {
-        let suit = Suit::get(random());
-        // hand
-        let card1 = self.deal_suit(suit)?;
-        let card2 = self.deal_suit(suit)?;
-        let hand = Hand([card1, card2]);
-        // board
-        let card3 = self.deal_suit(suit)?;
-        let card4 = self.deal_suit(suit)?;
-        // last card must not be same suit
-        let mut card5 = self._deal_one_random()?;
-        while card5.0 == suit {
-            card5 = self._deal_one_random()?;
-        }
-        self.remove_card(&card5);
-        let flop = Flop ([card3, card4, card5]);
-
-        //turn
-        let mut turn = self._deal_one_random()?;
-        while turn.0 == suit
-           || turn.1 == (hand.0[0]).1
-           || turn.1 == (hand.0[1]).1
-        {
-            turn = self._deal_one_random()?;
-        }
-        self.remove_card(&turn);
-
-        Some((flop, hand, turn))
-    }
-
-    pub fn hole_card(&mut self) -> Option<(Flop, Hand, Card)> {
-        let start = random::<u64>() % 8;
-        // hand
-        let card1 = self.deal_rank(Rank::get(start))?;
-        let card2 = self.deal_rank(Rank::get(start+1))?;
-        let hand = Hand ([card1, card2]);
-        // board
-        let card3 = self.deal_rank(Rank::get(start+3))?;
-        let card4 = self.deal_rank(Rank::get(start+4))?;
-        let mut card5 = self._deal_one_random()?;
-        while card5.1 == Rank::get(start+2) {
-            card5 = self._deal_one_random()?;
-        }
-        self.remove_card(&card5);
-        let flop = Flop ([card3, card4, card5]);
-
-        //turn
-        let mut turn = self._deal_one_random()?;
-        while turn.1 == Rank::get(start+2)
-           || turn.1 == (hand.0[0]).1
-           || turn.1 == (hand.0[1]).1
-        {
-            turn = self._deal_one_random()?;
-        }
-        self.remove_card(&turn);
+const THREE_BET_RANGES: [&str; 3] = [
+    "TT+, AQs+, AKo, A5s-A4s, KQs",
+    "QQ+, AK, A5s",
+    "88+, ATs+, KJs+, AQo+",
+];
 
-        Some((flop, hand, turn))
-    }
+/// Villain range when reviewing a hand that didn't reach showdown, or
+/// charting a scenario's equity, and no range is set
+const REVIEW_RANGE: &str = "22+, A2s+, K5s+, Q8s+, J8s+, T8s+, 97s+, 86s+, 75s+, 65s, 54s, A7o+, KTo+, QTo+, JTo";
 
-    pub fn de_straight(&mut self) -> Option<(Flop, Hand, Card)> {
-        let start = random::<u64>() % 7;
-        // hand
-        let card1 = self.deal_rank(Rank::get(start+1))?;
-        let card2 = self.deal_rank(Rank::get(start+2))?;
-        let hand = Hand ([card1, card2]);
-        // board
-        let card3 = self.deal_rank(Rank::get(start+3))?;
-        let card4 = self.deal_rank(Rank::get(start+4))?;
-        let mut card5 = self.deal_one_rand()?;
-        while card5.1 == Rank::get(start+5)
-           || card5.1 == Rank::get(start)
-        {
-            card5 = self.deal_one_rand()?;
-        }
-        let flop = Flop ([card3, card4, card5]);
-
-        //turn
-        let mut turn = self._deal_one_random()?;
-        while turn.1 == Rank::get(start)
-           || turn.1 == Rank::get(start+5)
-           || turn.1 == (hand.0[0]).1
-           || turn.1 == (hand.0[1]).1
-        {
-            turn = self._deal_one_random()?;
-        }
-        self.remove_card(&turn);
-
-        Some((flop, hand, turn))
-    }
-}
+/// Deals one of the scenarios and names it
+type Deal = fn(&mut Scenario) -> &'static str;
 
-pub struct Pot {
-    pub size: f32,
-}
+/// Whether a flop looks like one a scenario deals
+type Shape = fn(&[Card]) -> bool;
 
-impl Pot {
-    pub fn new(size: f32) -> Self {
-        Pot {
-            size
-        }
-    }
-    pub fn rand_pot() -> Self {
-        Pot::new(SIZES[random::<usize>() % 10])
-    }
-    pub fn rand_bet(&self) -> f32 {
-        self.size * BETS[random::<usize>() % 10]
-    }
-    pub fn odds(&self, bet: f32) -> f32 {
-        bet / (bet + self.size)
-    }
+/// Whether two cards of the flop are neighbouring ranks
+fn neighbours(flop: &[Card]) -> bool {
+    flop.iter().any(|a| flop.iter().any(|b| a.1.value() + 1 == b.1.value()))
 }
 
 struct Scenario {
+    config: Config,
     pot: Pot,
     bet: f32,
     flop: Option<Flop>,
     hand: Option<Hand>,
     turn: Option<Card>,
+    outs: Option<u8>,
     equity: Option<f32>,
     turn_equity: Option<f32>,
+    /// Cards hero can't see on the flop
+    unseen: u8,
+    /// Shown after the hand, for scenarios from a pack
+    note: Option<String>,
 }
 
 
@@ -228,35 +86,121 @@ fn clear_txt() {
 }
 
 impl Scenario {
-    fn new() -> Self {
-        let pot = Pot::rand_pot();
+    fn new(config: &Config) -> Self {
+        let pot = Pot::rand_pot(config);
         let mut ret = Self {
+            config: config.clone(),
             pot,
             bet: 0.,
             flop: None,
             hand: None,
             equity: None,
             turn: None,
+            outs: None,
             turn_equity: None,
+            unseen: config.deck_size() - 5,
+            note: None,
         };
         ret.init();
         ret
     }
 
     fn init(&mut self) {
-        match random::<u8>() % 3 {
-            0 => self.hole_card(),
-            1 => self.de_straight_draw(),
-            2 => self.flush_draw(),
-            _ => unimplemented!(),
-        };
+        // each scenario's flop: two neighbouring ranks for the straight
+        // draws, two of hero's suit and one other for the flush draw
+        let kinds: [(Deal, Shape); 3] = [
+            (Scenario::hole_card, neighbours),
+            (Scenario::de_straight_draw, neighbours),
+            (Scenario::flush_draw, |flop| Texture::of(flop).suits == Suits::TwoTone),
+        ];
+        let textures = &self.config.flop_textures;
+        let deals: Vec<Deal> = kinds.iter()
+            .filter(|(_, shape)| texture::any_flop(textures, self.config.lowest_rank(), shape))
+            .map(|&(deal, _)| deal)
+            .collect();
+        if deals.is_empty() {
+            append_txt(&format!("No scenario deals a flop that is {}, dealing any", textures.join(" and ")));
+        } else {
+            // redeal until the flop has the configured textures
+            for _ in 0..1000 {
+                let name = deals[random::<usize>() % deals.len()](self);
+                let texture = Texture::of(&self.flop.unwrap().0);
+                if self.config.flop_textures.iter().all(|t| texture.is(t)) {
+                    append_txt(&format!("Scenario: {}", name));
+                    return;
+                }
+            }
+            append_txt("No flop with the configured textures, dealing any");
+        }
+        let name = self.flush_draw();
+        append_txt(&format!("Scenario: {}", name));
     }
 
-    fn flop(&mut self) {
-        self.bet = self.pot.rand_bet();
-        append_txt(&format!("Pot ${}, bet ${}", self.pot.size, self.bet));
-        append_txt(&format!("Flop: {}", self.flop.unwrap()));
+    /// Plays a saved scenario again
+    fn from_record(config: &Config, record: &ScenarioRecord) -> Self {
+        Self {
+            config: config.clone(),
+            pot: Pot::with_rake(record.pot, config.rake),
+            bet: record.bet,
+            flop: Some(record.flop),
+            hand: Some(record.hand),
+            turn: record.turn,
+            outs: record.outs,
+            equity: record.equity,
+            turn_equity: record.turn_equity,
+            unseen: config.deck_size() - 5,
+            note: None,
+        }
+    }
+
+    fn record(&self) -> ScenarioRecord {
+        ScenarioRecord {
+            pot: self.pot.size,
+            bet: self.bet,
+            hand: self.hand.unwrap(),
+            flop: self.flop.unwrap(),
+            turn: self.turn,
+            outs: self.outs,
+            equity: self.equity,
+            turn_equity: self.turn_equity,
+        }
+    }
+
+    /// Chance to hit with the next card, facing the bet on `street`
+    fn street_equity(&self, street: Street) -> f32 {
+        if street == Street::Flop { self.equity } else { self.turn_equity }.unwrap()
+    }
+
+    /// Grades calling or folding the current bet, before `answer` moves
+    /// the pot on
+    fn decide(&self, scenario: usize, street: Street, called: bool) -> DecisionRecord {
+        let equity = self.street_equity(street);
+        let ev = self.pot.ev(self.bet, equity).net;
+        DecisionRecord {
+            scenario,
+            street,
+            action: if called { Action::Call } else { Action::Fold },
+            pot: self.pot.size,
+            bet: self.bet,
+            equity,
+            required: self.pot.required_equity(self.bet),
+            ev,
+            correct: called == (ev > 0.),
+        }
+    }
+
+    fn deal_flop(&mut self) {
+        // imported scenarios come with their bet
+        if self.bet <= 0. {
+            self.bet = self.pot.rand_bet(&self.config);
+        }
+        append_txt(&format!("Pot {}, bet {}", self.config.money(self.pot.size), self.config.money(self.bet)));
+        append_txt(&format!("Flop: {} ({})", self.flop.unwrap(), Texture::of(&self.flop.unwrap().0)));
         append_txt(&format!("Hand: {}", self.hand.unwrap()));
+    }
+
+    fn flop(&mut self) {
+        self.deal_flop();
         append_txt(&format!("
         <div class='flop'>
             <button>Call</button>
@@ -265,20 +209,81 @@ impl Scenario {
         "));
     }
 
-    fn answer(&mut self) {
+    /// Explains the call on `street`, with hero's equity spread over the
+    /// hands of villain's `range` on the board so far
+    fn answer(&mut self, street: Street, range: &Range) {
         let pot_odds = self.pot.odds(self.bet);
-        append_txt(&format!("<div class='answer'>Pot odds: {:.2}, equity: {:.2}</div>", pot_odds, self.equity.unwrap()));
-        let eqt = self.turn_equity.unwrap();
+        let outs = self.outs.unwrap();
+        // one card to come either way, from one card fewer on the turn
+        let eqt = self.street_equity(street);
+        let unseen = if street == Street::Flop { self.unseen } else { self.unseen - 1 };
+        append_txt(&format!("<div class='answer'>Pot odds: {}, need {} equity</div>",
+            odds::format_ratio(1. / pot_odds), odds::format_percent(self.pot.required_equity(self.bet))));
+        append_txt(&format!("<div class='answer'>Equity: {} outs = {} ({}), rule of 2: {} (off by {})</div>",
+            outs, odds::format_percent(eqt), odds::format_ratio(odds::equity_to_ratio(eqt)),
+            odds::format_percent(odds::rule_of_2_4(outs, 1)), odds::format_percent(odds::rule_of_2_4_error(outs, unseen, 1))));
+        if street == Street::Flop {
+            let river = odds::outs_to_equity(outs, unseen, 2);
+            append_txt(&format!("<div class='answer'>By the river: {} ({}), rule of 4: {} (off by {})</div>",
+                odds::format_percent(river), odds::format_ratio(odds::equity_to_ratio(river)),
+                odds::format_percent(odds::rule_of_2_4(outs, 2)), odds::format_percent(odds::rule_of_2_4_error(outs, unseen, 2))));
+        }
         let potsize = self.pot.size + self.bet;
-        let ev = potsize * eqt - self.bet * (1. - eqt);
-        append_txt(&format!("<div class='answer'>EV = ${:.2} x {:.2} - ${:.2} * {:.2} = {:.2}</div>", potsize, eqt, self.bet, 1. - eqt, ev));
+        let ev = self.pot.ev(self.bet, eqt);
+        let money = |x| self.config.money(x);
+        append_txt(&format!("<div class='answer'>Gross EV = {} x {:.2} - {} * {:.2} = {}</div>", money(potsize), eqt, money(self.bet), 1. - eqt, money(ev.gross)));
+        append_txt(&format!("<div class='answer'>Net EV = ({} - {} rake) x {:.2} - {} * {:.2} = {}</div>", money(potsize), money(ev.rake), eqt, money(self.bet), 1. - eqt, money(ev.net)));
+        let mut board = self.flop.unwrap().0.to_vec();
+        if street == Street::Turn {
+            board.push(self.turn.unwrap());
+        }
+        let dist = equity::distribution(&self.hand.unwrap(), &range.combos(), &board);
+        if let Some(mean) = dist.mean() {
+            append_txt(&format!("<div class='answer'>Against {}: {} on average</div>", range, odds::format_percent(mean)));
+            append_txt(&chart(&dist));
+        }
         self.pot.size += self.bet * 2.;
     }
 
+    /// Deals the flop and asks for one number about the call
+    fn quiz(&mut self) -> Quiz {
+        use Question::*;
+        self.deal_flop();
+        let question = Question::get(random());
+        let odds = self.pot.odds(self.bet);
+        match question {
+            RequiredEquity => Quiz::new(question, self.pot.required_equity(self.bet), 0.02),
+            PotOdds => Quiz::new(question, 1. / odds, 0.1 / odds),
+            Outs => Quiz::new(question, f32::from(self.outs.unwrap()), 0.5),
+            Ev => Quiz::new(question, self.pot.ev(self.bet, self.equity.unwrap()).net, self.bet * 0.1),
+        }
+    }
+
+    fn reveal(&self, quiz: &Quiz) {
+        use Question::*;
+        let money = |x| self.config.money(x);
+        let show = |x| quiz.question.show(x, &self.config);
+        let win = self.pot.size + self.bet - self.pot.rake(self.bet);
+        let formula = match quiz.question {
+            RequiredEquity => format!("{} / ({} + {}) = {}", money(self.bet), money(win), money(self.bet), show(quiz.expected)),
+            PotOdds => format!("{} : {} = {}", money(win), money(self.bet), show(quiz.expected)),
+            Outs => format!("equity {:.2} = {} / {}", self.equity.unwrap(), show(quiz.expected), self.unseen),
+            Ev => {
+                let eqt = self.equity.unwrap();
+                format!("{} x {:.2} - {} x {:.2} = {}", money(win), eqt, money(self.bet), 1. - eqt, show(quiz.expected))
+            }
+        };
+        append_txt(&format!("<div class='answer'>{}</div>", formula));
+    }
+
     fn turn(&mut self) {
-        self.bet = self.pot.rand_bet();
+        self.bet = self.pot.rand_bet(&self.config);
         append_txt(&format!("Turn: {}.", self.turn.unwrap()));
-        append_txt(&format!("Pot ${:.2}, Bet ${:.2}", self.pot.size, self.bet));
+        let change = Change::of(&self.flop.unwrap().0, self.turn.unwrap());
+        for what in change.describe() {
+            append_txt(&format!("<div class='answer'>The turn {}</div>", what));
+        }
+        append_txt(&format!("Pot {}, Bet {}", self.config.money(self.pot.size), self.config.money(self.bet)));
         append_txt(&format!("
             <div class='turn'>
                 <button>Call</button>
@@ -287,42 +292,451 @@ impl Scenario {
         "));
     }
 
-    fn flush_draw(&mut self) {
-        append_txt("Scenario: Flush Draw");
-        let mut deck = Deck::new();
-        let (flop, hand, turn) = deck.flush_draw().unwrap();
-        self.flop = Some(flop);
-        self.hand = Some(hand);
-        self.turn = Some(turn);
-        self.equity = Some(9./47.);
-        self.turn_equity = Some(9./46.);
+    fn set_outs(&mut self, outs: u8) {
+        self.outs = Some(outs);
+        self.equity = Some(f32::from(outs) / f32::from(self.unseen));
+        self.turn_equity = Some(f32::from(outs) / f32::from(self.unseen - 1));
     }
 
-    fn de_straight_draw(&mut self) {
-        append_txt("Scenario: Straight Draw");
-        let mut deck = Deck::new();
-        let (flop, hand, turn) = deck.de_straight().unwrap();
+    /// Takes a dealt draw that `outs` cards complete. In short deck the
+    /// outs are counted instead, as the rivers `evaluate_short_deck7`
+    /// makes a straight or better with.
+    fn set_draw(&mut self, (flop, hand, turn): (Flop, Hand, Card), outs: u8) {
         self.flop = Some(flop);
         self.hand = Some(hand);
         self.turn = Some(turn);
-        self.equity = Some(8./47.);
-        self.turn_equity = Some(8./46.);
+        if !self.config.short_deck {
+            self.set_outs(outs);
+            return;
+        }
+        let mut deck = Deck::for_config(&self.config);
+        let dealt = [hand.0[0], hand.0[1], flop.0[0], flop.0[1], flop.0[2], turn];
+        for card in &dealt {
+            deck.remove_card(card);
+        }
+        let mut cards = [0; 7];
+        for (id, card) in cards.iter_mut().zip(&dealt) {
+            *id = card.to_int();
+        }
+        let outs = deck.cards().iter().filter(|river| {
+            cards[6] = river.to_int();
+            evaluate::category(evaluate::evaluate_short_deck7(cards)) >= Category::Straight
+        }).count();
+        self.set_outs(outs as u8);
     }
 
-    fn hole_card(&mut self) {
-        append_txt("Scenario: Straight Draw");
-        let mut deck = Deck::new();
-        let (flop, hand, turn) = deck.hole_card().unwrap();
-        self.flop = Some(flop);
-        self.hand = Some(hand);
-        self.turn = Some(turn);
-        self.equity = Some(4./47.);
-        self.turn_equity = Some(4./46.);
+    fn flush_draw(&mut self) -> &'static str {
+        let mut deck = Deck::for_config(&self.config);
+        // the rest of the suit, five in short deck
+        let outs = self.config.deck_size() / 4 - 4;
+        self.set_draw(deck.flush_draw().unwrap(), outs);
+        "Flush Draw"
+    }
+
+    fn de_straight_draw(&mut self) -> &'static str {
+        let mut deck = Deck::for_config(&self.config);
+        self.set_draw(deck.de_straight().unwrap(), 8);
+        "Straight Draw"
+    }
+
+    fn hole_card(&mut self) -> &'static str {
+        let mut deck = Deck::for_config(&self.config);
+        self.set_draw(deck.hole_card().unwrap(), 4);
+        "Straight Draw"
+    }
+}
+
+/// Facing an all-in or a 3-bet before the flop with a known villain range
+struct Preflop {
+    config: Config,
+    pot: Pot,
+    bet: f32,
+    hand: Hand,
+    range: Range,
+    action: String,
+}
+
+impl Preflop {
+    /// Against `range` if one is set, otherwise a typical one for the action
+    fn new(config: &Config, range: Option<Range>) -> Self {
+        let mut deck = Deck::for_config(config);
+        let hand = Hand([deck.deal_one_rand().unwrap(), deck.deal_one_rand().unwrap()]);
+        let (bb, sb) = (config.big_blind, config.small_blind);
+        // the pot holds what hero wins on top of getting the call back
+        let (action, size, bet, ranges) = if random::<bool>() {
+            let shove = (10 + random::<u64>() % 21) as f32 * bb;
+            let action = format!("You are in the big blind, the small blind shoves {}", config.money(shove));
+            (action, bb * 2., shove - bb, &SHOVE_RANGES[..])
+        } else {
+            let (open, three) = (bb * 2.5, bb * (8 + random::<u64>() % 5) as f32);
+            let action = format!("You open to {} on the button, the big blind 3-bets to {}",
+                config.money(open), config.money(three));
+            (action, open * 2. + sb, three - open, &THREE_BET_RANGES[..])
+        };
+        let range = range.unwrap_or_else(|| ranges[random::<usize>() % ranges.len()].parse().unwrap());
+        Self {
+            config: config.clone(),
+            pot: Pot::with_rake(size, config.rake),
+            bet,
+            hand,
+            range,
+            action,
+        }
+    }
+
+    fn show(&self) {
+        append_txt("Scenario: Preflop");
+        append_txt(&self.action);
+        append_txt(&format!("Villain range: {}", self.range));
+        append_txt(&format!("Pot {}, call {}", self.config.money(self.pot.size + self.bet * 2.), self.config.money(self.bet)));
+        append_txt(&format!("Hand: {}", self.hand));
+        append_txt("
+        <div class='preflop'>
+            <button>Call</button>
+            <button>Fold</button>
+        </div>
+        ");
+    }
+
+    fn answer(&self) {
+        let dist = preflop::distribution(&self.hand, &self.range);
+        let equity = dist.mean().unwrap_or(0.);
+        let need = self.pot.required_equity(self.bet);
+        let ev = self.pot.ev(self.bet, equity);
+        append_txt(&format!("<div class='answer'>Pot odds: {}, need {} equity</div>",
+            odds::format_ratio(1. / self.pot.odds(self.bet)), odds::format_percent(need)));
+        append_txt(&format!("<div class='answer'>Equity against {}: {}</div>", self.range, odds::format_percent(equity)));
+        append_txt(&chart(&dist));
+        append_txt(&format!("<div class='answer'>Net EV of calling = {} ({} rake), {}</div>",
+            self.config.money(ev.net), self.config.money(ev.rake),
+            if ev.net > 0. { "call" } else { "fold" }));
+        append_txt(&format!("<div class='answer'>{}</div>", self.grid().html()));
+    }
+
+    /// Villain's range against hero's hand before the flop
+    fn grid(&self) -> RangeGrid {
+        let mut grid = RangeGrid { range: Some(self.range.clone()), ..RangeGrid::default() };
+        grid.deal(&self.hand, &[]);
+        grid
     }
 }
 
+/// Omaha hi-lo on the flop against a known hand, where the pot can be
+/// scooped, split or quartered
+struct HiLo {
+    config: Config,
+    pot: Pot,
+    bet: f32,
+    hand: OmahaHand,
+    villain: OmahaHand,
+    flop: Flop,
+    split: Split,
+}
+
+impl HiLo {
+    fn new(config: &Config) -> Self {
+        let mut deck = Deck::for_config(config);
+        let mut deal = || deck.deal_one_rand().unwrap();
+        let hand = OmahaHand([deal(), deal(), deal(), deal()]);
+        let villain = OmahaHand([deal(), deal(), deal(), deal()]);
+        let flop = Flop([deal(), deal(), deal()]);
+        let pot = Pot::rand_pot(config);
+        let bet = pot.rand_bet(config);
+        Self {
+            config: config.clone(),
+            pot,
+            bet,
+            hand,
+            villain,
+            flop,
+            split: equity::omaha8_vs_omaha8(&hand, &villain, &flop.0),
+        }
+    }
+
+    fn show(&self) {
+        append_txt("Scenario: Omaha Hi-Lo");
+        append_txt(&format!("Pot {}, bet {}", self.config.money(self.pot.size), self.config.money(self.bet)));
+        append_txt(&format!("Flop: {}", self.flop));
+        append_txt(&format!("Hand: {}", self.hand));
+        append_txt("
+        <div class='hilo'>
+            <button>Call</button>
+            <button>Fold</button>
+        </div>
+        ");
+    }
+
+    fn answer(&self) {
+        let money = |x| self.config.money(x);
+        let pot = self.pot.size + self.bet * 2. - self.pot.rake(self.bet);
+        let equity = self.split.equity();
+        let ev = self.pot.ev(self.bet, equity);
+        append_txt(&format!("<div class='answer'>Villain: {}</div>", self.villain));
+        append_txt(&format!("<div class='answer'>Need {} of the pot</div>",
+            odds::format_percent(self.pot.required_equity(self.bet))));
+        // pot shares from a scoop down to a quarter
+        let mut terms = Vec::new();
+        for &(quarters, name) in &[(4, "scoop"), (3, "three quarters"), (2, "half"), (1, "quarter")] {
+            let chance = self.split.chance(quarters);
+            if chance > 0. {
+                append_txt(&format!("<div class='answer'>{}: {}</div>", name, odds::format_percent(chance)));
+                terms.push(format!("{:.2} x {}", chance, money(pot * quarters as f32 / 4.)));
+            }
+        }
+        append_txt(&format!("<div class='answer'>Net EV = {} - {} = {}, {}</div>",
+            terms.join(" + "), money(self.bet), money(ev.net),
+            if ev.net > 0. { "call" } else { "fold" }));
+    }
+}
+
+/// A call or fold hero made in a hand history, as a scenario
+struct Review {
+    street: Street,
+    pot: Pot,
+    bet: f32,
+    big_blind: f32,
+    hand: Hand,
+    flop: Option<Flop>,
+    board: Vec<Card>,
+    /// What the player whose bet hero faced showed down, if anything
+    villain: Option<Hand>,
+    called: bool,
+}
+
+impl Review {
+    /// The spots in `history` where hero called or folded to a bet, raked
+    /// as `rake`
+    fn from_history(history: &HandHistory, rake: Rake) -> Vec<Self> {
+        history.decisions.iter()
+            .filter(|d| d.to_call > 0. && (d.action == Action::Call || d.action == Action::Fold))
+            .map(|d| Review {
+                street: d.street,
+                // the pot holds the bet hero faces
+                pot: Pot::with_rake(d.pot - d.to_call, rake),
+                bet: d.to_call,
+                big_blind: history.big_blind,
+                hand: history.hero,
+                flop: if d.board.len() >= 3 { Some(Flop([d.board[0], d.board[1], d.board[2]])) } else { None },
+                board: d.board.clone(),
+                villain: history.bettor_shown(d),
+                called: d.action == Action::Call,
+            })
+            .collect()
+    }
+
+    /// Equity against the hand villain showed, or else against `range`
+    fn equity(&self, range: &Range) -> Option<f32> {
+        match (self.villain, self.board.is_empty()) {
+            (Some(villain), true) => Some(preflop::equity(HandClass::of(&self.hand), HandClass::of(&villain))),
+            (Some(villain), false) => Some(equity::hand_vs_hand(&self.hand, &villain, &self.board).equity()),
+            (None, true) => preflop::hand_vs_range(&self.hand, range),
+            (None, false) => equity::hand_vs_range(&self.hand, range, &self.board),
+        }
+    }
+
+    /// Shows the spot and whether hero's call or fold beat the pot odds,
+    /// `None` if there's no equity to grade against
+    fn grade(&self, range: &Range) -> Option<bool> {
+        let bb = |x: f32| format!("{:.1}bb", x / self.big_blind);
+        let board = match self.flop {
+            Some(flop) => {
+                let rest: Vec<String> = self.board[3..].iter().map(|c| c.to_string()).collect();
+                format!(" {}{}", flop, rest.concat())
+            }
+            None => String::new(),
+        };
+        append_txt(&format!("{:?}{}: pot {}, {} to call", self.street, board,
+            bb(self.pot.size + self.bet), bb(self.bet)));
+
+        let equity = self.equity(range)?;
+        let need = self.pot.required_equity(self.bet);
+        let ev = self.pot.ev(self.bet, equity);
+        let against = match self.villain {
+            Some(villain) => format!("{}", villain),
+            None => format!("{}", range),
+        };
+        let right = self.called == (ev.net > 0.);
+        append_txt(&format!("<div class='answer'>Need {}, equity against {}: {}</div>",
+            odds::format_percent(need), against, odds::format_percent(equity)));
+        append_txt(&format!("<div class='answer'>You {}, EV of calling = {}: {}</div>",
+            if self.called { "called" } else { "folded" }, bb(ev.net),
+            if right { "right" } else { "mistake" }));
+        Some(right)
+    }
+}
+
+/// Grades hero's calls and folds in the hand histories in `text` against
+/// pot odds
+fn review(text: &str, range: Option<Range>, config: &Config) {
+    let (hands, errors) = history::parse(text);
+    for e in &errors {
+        append_txt(&format!("Skipped a hand: {}", e));
+    }
+    let range = range.unwrap_or_else(|| REVIEW_RANGE.parse().unwrap());
+    let (mut right, mut graded) = (0, 0);
+    for hand in &hands {
+        let reviews = Review::from_history(hand, config.rake);
+        if reviews.is_empty() {
+            continue;
+        }
+        append_txt(&format!("Hand #{}: {}", hand.id, hand.hero));
+        for r in &reviews {
+            if let Some(ok) = r.grade(&range) {
+                graded += 1;
+                if ok {
+                    right += 1;
+                }
+            }
+        }
+    }
+    append_txt(&format!("{} hands read, {} of {} calls and folds right", hands.len(), right, graded));
+}
+
+/// Villain's range as a 13x13 grid of hand classes, suited hands above the
+/// diagonal. Each cell is colored by hero's equity against it on the
+/// current board, from red when behind to green when ahead, and faded by
+/// its weight in the range.
+#[derive(Default)]
+struct RangeGrid {
+    range: Option<Range>,
+    hand: Option<Hand>,
+    board: Vec<Card>,
+    /// By `HandClass::index`, worked out when first shown
+    equities: Vec<Option<f32>>,
+}
+
+impl RangeGrid {
+    /// Colors the grid for a new hand or board
+    fn deal(&mut self, hand: &Hand, board: &[Card]) {
+        self.hand = Some(*hand);
+        self.board = board.to_vec();
+        self.equities.clear();
+    }
+
+    fn equities(&mut self) {
+        if !self.equities.is_empty() {
+            return;
+        }
+        if let Some(hand) = self.hand {
+            // all 1326 combos on a flop is fine, the preflop runouts are not
+            self.equities = if self.board.is_empty() {
+                preflop::equity_by_class(&hand)
+            } else {
+                equity::equity_by_class(&hand, &self.board)
+            };
+        }
+    }
+
+    /// Cycles a cell through full, half and no weight
+    fn click(&mut self, i: usize) {
+        let range = self.range.get_or_insert_with(Range::empty);
+        let class = HandClass::from_index(i);
+        let weight = match range.get(class) {
+            w if w >= 1. => 0.5,
+            w if w > 0. => 0.,
+            _ => 1.,
+        };
+        range.set(class, weight);
+    }
+
+    fn html(&mut self) -> String {
+        self.equities();
+        let empty = Range::empty();
+        let range = self.range.as_ref().unwrap_or(&empty);
+        let mut rows = String::new();
+        for row in 0..13 {
+            let cells: Vec<String> = (row * 13..row * 13 + 13).map(|i| {
+                let class = HandClass::from_index(i);
+                let (color, title) = match self.equities.get(i) {
+                    Some(&Some(e)) => (format!("hsl({:.0}, 70%, 70%)", e * 120.), odds::format_percent(e)),
+                    Some(&None) => ("lightgrey".to_string(), "blocked".to_string()),
+                    None => ("white".to_string(), String::new()),
+                };
+                format!("<td data-index='{}' title='{}' style='background: {}; opacity: {:.2}'>{}</td>",
+                    i, title, color, 0.25 + 0.75 * range.get(class), class)
+            }).collect();
+            rows += &format!("<tr>{}</tr>", cells.concat());
+        }
+        format!("<table class='grid'>{}</table>", rows)
+    }
+}
+
+/// Shows the range editor: the range in notation above its grid, each
+/// kept in step with the other
+fn edit_range(grid: Rc<RefCell<RangeGrid>>) {
+    {
+        let mut g = grid.borrow_mut();
+        append_txt("Villain range for the preflop spots. Click a cell for full, half or no weight.");
+        if let Some(hand) = g.hand {
+            let board: Vec<String> = g.board.iter().map(|c| c.to_string()).collect();
+            append_txt(&format!("Colored by equity of {} on [{}]", hand, board.concat()));
+        }
+        let text = g.range.as_ref().map(|r| r.to_string()).unwrap_or_default();
+        append_txt(&format!("
+        <div class='range'>
+            <input type='text' size='60' value='{}' />
+            <button>Set</button>
+            <span></span>
+        </div>
+        <div id='grid'>{}</div>
+        ", text, g.html()));
+    }
+    fn redraw(grid: &mut RangeGrid) {
+        let text = grid.range.as_ref().map(|r| r.to_string()).unwrap_or_default();
+        let html = grid.html();
+        js! {
+            document.querySelector(".range input").value = @{text};
+            document.querySelector(".range span").textContent = "";
+            document.querySelector("#grid").innerHTML = @{html};
+        }
+    }
+
+    let g = grid.clone();
+    let set = document().query_selector( ".range button" ).unwrap().unwrap();
+    set.add_event_listener( move |_: ClickEvent| {
+        let input = js! {
+            return document.querySelector(".range input").value;
+        }.into_string().unwrap_or_default();
+        let parsed = if input.trim().is_empty() { Ok(None) } else { input.parse::<Range>().map(Some) };
+        match parsed {
+            Ok(range) => {
+                g.borrow_mut().range = range;
+                redraw(&mut g.borrow_mut());
+            }
+            Err(e) => {
+                js! {
+                    document.querySelector(".range span").textContent = @{e};
+                }
+            }
+        }
+    });
+    let cells = document().query_selector( "#grid" ).unwrap().unwrap();
+    cells.add_event_listener( move |e: ClickEvent| {
+        let index = js! {
+            return @{e}.target.dataset.index;
+        }.into_string().and_then(|s| s.parse().ok());
+        if let Some(i) = index {
+            grid.borrow_mut().click(i);
+            redraw(&mut grid.borrow_mut());
+        }
+    });
+}
+
+/// Bar chart of how often each tenth of equity comes up, with quartiles
+fn chart(dist: &EquityDistribution) -> String {
+    let bars: Vec<String> = dist.histogram(10).iter().enumerate()
+        .map(|(i, share)| format!(
+            "<div class='bar' title='{}-{}%: {}' style='height: {:.0}px'></div>",
+            i * 10, i * 10 + 10, odds::format_percent(*share), share * 100.))
+        .collect();
+    let quartile = |p| dist.percentile(p).map(odds::format_percent).unwrap_or_default();
+    format!("<div class='answer'><div class='chart'>{}</div>0% to 100% equity, quartiles {} / {} / {}</div>",
+        bars.concat(), quartile(0.25), quartile(0.5), quartile(0.75))
+}
+
 use std::rc::Rc;
 use std::cell::RefCell;
+use std::collections::VecDeque;
 
 fn remove(selector: &str) {
     js! {
@@ -332,28 +746,496 @@ fn remove(selector: &str) {
 
 }
 
+/// Whether the Call or the Fold button was clicked, `None` for the space
+/// around them
+fn call_or_fold(e: ClickEvent) -> Option<bool> {
+    let text = js! {
+        return @{e}.target.textContent;
+    }.into_string()?;
+    match text.trim() {
+        "Call" => Some(true),
+        "Fold" => Some(false),
+        _ => None,
+    }
+}
+
+/// Scenarios for "New" to deal before random ones, each with a title
+type Queue = Rc<RefCell<VecDeque<(String, Scenario)>>>;
+
+/// Shows the session as JSON and CSV, with a box to import scenarios
+/// from JSON that "New" then deals in order
+fn show_session(session: &Session, config: Rc<Config>, imported: Queue) {
+    let right = session.decisions.iter().filter(|d| d.correct).count();
+    append_txt(&format!("{} scenarios, {} of {} decisions right", session.scenarios.len(), right, session.decisions.len()));
+    append_txt(&format!("JSON<br /><textarea rows='12' cols='80' readonly>{}</textarea>", session.to_json()));
+    append_txt(&format!("CSV<br /><textarea rows='8' cols='80' readonly>{}</textarea>", session.to_csv()));
+    append_txt("
+    <div class='import'>
+        Import scenarios<br />
+        <textarea rows='8' cols='80'></textarea>
+        <button>Import</button>
+    </div>
+    ");
+    let submit = document().query_selector( ".import button" ).unwrap().unwrap();
+    submit.add_event_listener( move |_: ClickEvent| {
+        let text = js! {
+            return document.querySelector(".import textarea").value;
+        }.into_string().unwrap_or_default();
+        let session = Session::from_json(&text).and_then(|s| {
+            for (i, r) in s.scenarios.iter().enumerate() {
+                Deck::without(&config, &r.cards()).map_err(|e| format!("scenario {}: {}", i, e))?;
+            }
+            Ok(s)
+        });
+        match session {
+            Ok(s) => {
+                remove(".import");
+                append_txt(&format!("Imported {} scenarios, press New to play them", s.scenarios.len()));
+                imported.borrow_mut().extend(s.scenarios.iter()
+                    .map(|r| ("Imported".to_string(), Scenario::from_record(&config, r))));
+            }
+            Err(e) => append_txt(&format!("Can't import: {}", e)),
+        }
+    });
+}
+
+/// Lists the built-in packs and those in the page's `.pack` elements to
+/// step through in order or shuffled
+fn choose_pack(config: Rc<Config>, queue: Queue) {
+    let texts: Vec<String> = js! {
+        return Array.from(document.querySelectorAll(".pack")).map(x => x.textContent);
+    }.try_into().unwrap_or_default();
+    // built-in packs that don't fit the deck, e.g. in short deck, are left out
+    let mut packs: Vec<Pack> = packs::BUILT_IN.iter().filter_map(|t| Pack::parse(t, &config).ok()).collect();
+    for text in texts.iter().filter(|t| !t.trim().is_empty()) {
+        match Pack::parse(text, &config) {
+            Ok(pack) => packs.push(pack),
+            Err(e) => append_txt(&format!("Bad pack: {}", e)),
+        }
+    }
+    if packs.is_empty() {
+        append_txt("No scenario packs");
+        return;
+    }
+    let rows: Vec<String> = packs.iter().enumerate()
+        .map(|(i, p)| format!("<div>{} ({} hands) {}
+            <button data-choice='{}o'>In order</button>
+            <button data-choice='{}s'>Shuffled</button></div>",
+            p.name, p.hands.len(), p.description, i, i))
+        .collect();
+    append_txt(&format!("<div class='packs'>{}</div>", rows.concat()));
+    let list = document().query_selector( ".packs" ).unwrap().unwrap();
+    list.add_event_listener( move |e: ClickEvent| {
+        let choice = js! {
+            return @{e}.target.dataset.choice;
+        }.into_string().unwrap_or_default();
+        let index = choice.get(..choice.len().saturating_sub(1)).and_then(|i| i.parse::<usize>().ok());
+        let pack = match index.and_then(|i| packs.get(i)) {
+            Some(pack) => pack,
+            None => return,
+        };
+        let records = pack.records(&config, choice.ends_with('s'));
+        let n = records.len();
+        let mut queue = queue.borrow_mut();
+        queue.clear();
+        queue.extend(records.into_iter().enumerate().map(|(i, (record, note))| {
+            let mut scenario = Scenario::from_record(&config, &record);
+            if !note.is_empty() {
+                scenario.note = Some(note);
+            }
+            (format!("{}, hand {} of {}", pack.name, i + 1, n), scenario)
+        }));
+        remove(".packs");
+        append_txt(&format!("{} hands of {} queued, press New for each", n, pack.name));
+    });
+}
+
+/// Villain for played hands: checks and calls mostly, bets or raises half
+/// the pot a quarter of the time and folds to a fifth of the bets
+fn villain_action(game: &Game) -> (Action, f32) {
+    let legal = game.legal();
+    let actions = game.actions();
+    let x = random::<f32>();
+    if legal.call > 0. && x < 0.2 {
+        return (Action::Fold, 0.);
+    }
+    if let Some((least, most)) = legal.raise {
+        if x > 0.75 {
+            let me = &game.players[game.to_act];
+            let to = me.bet + legal.call + (game.pot_total() + legal.call) / 2.;
+            return (*actions.last().unwrap(), to.max(least).min(most));
+        }
+    }
+    (if legal.call > 0. { Action::Call } else { Action::Check }, 0.)
+}
+
+fn describe(logged: &Logged, config: &Config) -> String {
+    let who = if logged.seat == BUTTON { "You" } else { "Villain" };
+    match logged.action {
+        Action::Bet | Action::Raise => format!("{} {:?} to {}", who, logged.action, config.money(logged.to)),
+        _ => format!("{} {:?}", who, logged.action),
+    }
+}
+
+/// Plays villain's turns, then shows the hand and hero's options, or how
+/// it ended. Hero has the button.
+fn play(game: Rc<RefCell<Game>>, config: Rc<Config>) {
+    let shown = game.borrow().log.len();
+    while !game.borrow().is_over() && game.borrow().to_act != BUTTON {
+        let (action, to) = villain_action(&game.borrow());
+        game.borrow_mut().act(action, to).unwrap();
+    }
+    let g = game.borrow();
+    for logged in &g.log[shown..] {
+        append_txt(&describe(logged, &config));
+    }
+    let board: Vec<String> = g.board().iter().map(|c| c.to_string()).collect();
+    append_txt(&format!("{:?} {}, pot {}", g.street, board.concat(), config.money(g.pot_total())));
+
+    if g.outcome.is_some() {
+        show_outcome(&g, &config);
+        append_txt("<div class='replay'><button>Replay</button></div>");
+        let (game, config) = (game.clone(), config.clone());
+        let replay_btn = document().query_selector( ".replay" ).unwrap().unwrap();
+        replay_btn.add_event_listener( move |_: ClickEvent| {
+            remove(".replay");
+            replay(&game.borrow(), &config);
+        });
+        return;
+    }
+
+    let legal = g.legal();
+    let buttons: Vec<String> = g.actions().iter().map(|a| match *a {
+        Action::Call => format!("<button data-action='Call'>Call {}</button>", config.money(legal.call)),
+        a => format!("<button data-action='{:?}'>{:?}</button>", a, a),
+    }).collect();
+    // the exact amount, as rounding to cents could go below the least raise
+    let to = legal.raise.map(|(least, _)| least.to_string()).unwrap_or_default();
+    append_txt(&format!("
+        <div class='play'>
+            You have {}, stack {}
+            {}
+            <input type='text' size='6' value='{}' />
+        </div>
+    ", g.players[0].hand, config.money(g.players[0].stack), buttons.concat(), to));
+    drop(g);
+
+    let area = document().query_selector( ".play" ).unwrap().unwrap();
+    area.add_event_listener( move |e: ClickEvent| {
+        let name = js! {
+            return @{e}.target.dataset.action;
+        }.into_string().unwrap_or_default();
+        let action = match name.as_str() {
+            "Fold" => Action::Fold,
+            "Check" => Action::Check,
+            "Call" => Action::Call,
+            "Bet" => Action::Bet,
+            "Raise" => Action::Raise,
+            _ => return,
+        };
+        let input = js! {
+            return document.querySelector(".play input").value;
+        }.into_string().unwrap_or_default();
+        let to = odds::parse_number(&input).unwrap_or(0.);
+        let result = game.borrow_mut().act(action, to);
+        match result {
+            Ok(()) => {
+                remove(".play");
+                play(game.clone(), config.clone());
+            }
+            Err(e) => append_txt(&e),
+        }
+    });
+}
+
+/// Who won a finished hand and the stacks after it
+fn show_outcome(game: &Game, config: &Config) {
+    let outcome = match game.outcome {
+        Some(ref outcome) => outcome,
+        None => return,
+    };
+    if outcome.ranks.is_some() {
+        append_txt(&format!("Villain shows {}", game.players[1].hand));
+    }
+    let winner = match outcome.winners[..] {
+        [BUTTON] => "You win",
+        [_] => "Villain wins",
+        _ => "Split pot,",
+    };
+    append_txt(&format!("{} {} ({} rake)", winner, config.money(outcome.won), config.money(outcome.rake)));
+    append_txt(&format!("Stacks: you {}, villain {}",
+        config.money(game.players[0].stack), config.money(game.players[1].stack)));
+}
+
+/// Plays a finished hand again from its cards, street by street
+fn replay(game: &Game, config: &Config) {
+    let hands = [game.players[0].hand, game.players[1].hand];
+    let again = |actions: &[Logged]| Game::replay(config, game.stacks, hands, game.cards(), actions);
+    append_txt(&format!("Replay: you {}, villain {}", hands[0], hands[1]));
+    for (i, logged) in game.log.iter().enumerate() {
+        if i == 0 || logged.street != game.log[i - 1].street {
+            match again(&game.log[..i]) {
+                Ok(g) => {
+                    let board: Vec<String> = g.board().iter().map(|c| c.to_string()).collect();
+                    append_txt(&format!("{:?} {}, pot {}", g.street, board.concat(), config.money(g.pot_total())));
+                }
+                Err(e) => {
+                    append_txt(&e);
+                    return;
+                }
+            }
+        }
+        append_txt(&describe(logged, config));
+    }
+    match again(&game.log) {
+        Ok(g) => show_outcome(&g, config),
+        Err(e) => append_txt(&e),
+    }
+}
+
+/// Asks `quiz` with a text box, grades the answer and calls `reveal`
+fn ask<Q, F>(quiz: Quiz<Q>, config: Rc<Config>, score: Rc<RefCell<Score>>, reveal: F)
+    where Q: Ask + 'static, F: Fn(&Quiz<Q>) + 'static
+{
+    append_txt(&format!("
+        <div class='quiz'>
+            {}
+            <input type='text' />
+            <button>Submit</button>
+        </div>
+    ", quiz.question.prompt()));
+    let submit = document().query_selector( ".quiz button" ).unwrap().unwrap();
+    submit.add_event_listener( move |_: ClickEvent| {
+        let input = js! {
+            return document.querySelector(".quiz input").value;
+        }.into_string().unwrap_or_default();
+        match quiz.grade(&input) {
+            Some(grade) => {
+                remove(".quiz");
+                score.borrow_mut().add(&grade);
+                let show = |x| quiz.question.show(x, &config);
+                append_txt(&format!("{} You said {}, answer is {}", grade, show(grade.answer), show(grade.expected)));
+                reveal(&quiz);
+                append_txt(&format!("{}", score.borrow()));
+            }
+            None => append_txt("Type a number, e.g. 25%, 3 to 1 or 12.50"),
+        }
+    });
+}
+
+/// Reads the drill configuration from the page's `#config` element, if any
+fn load_config() -> Config {
+    let txt = js! {
+        let x = document.querySelector("#config");
+        return x ? x.textContent : "";
+    }.into_string().unwrap_or_default();
+    if txt.trim().is_empty() {
+        return Config::default();
+    }
+    match Config::parse(&txt) {
+        Ok(config) => config,
+        Err(e) => {
+            append_txt(&format!("Bad config, using defaults: {}", e));
+            Config::default()
+        }
+    }
+}
+
 fn main() {
     stdweb::initialize();
+    let config = Rc::new(load_config());
+    let score = Rc::new(RefCell::new(Score::default()));
+    let grid = Rc::new(RefCell::new(RangeGrid::default()));
+    let session = Rc::new(RefCell::new(Session::default()));
+    let imported = Rc::new(RefCell::new(VecDeque::new()));
+    let (c, g, ss, queue) = (config.clone(), grid.clone(), session.clone(), imported.clone());
     let button = document().query_selector( "#new" ).unwrap().unwrap();
     button.add_event_listener( move |_: ClickEvent| {
         clear_txt();
-        let s = Rc::new(RefCell::new(Scenario::new()));
+        let scenario = match queue.borrow_mut().pop_front() {
+            Some((title, scenario)) => {
+                append_txt(&format!("Scenario: {}", title));
+                scenario
+            }
+            None => Scenario::new(&c),
+        };
+        let s = Rc::new(RefCell::new(scenario));
         let s_clone = s.clone();
         s_clone.borrow_mut().flop();
+        let index = ss.borrow_mut().add(s.borrow().record());
+        g.borrow_mut().deal(&s.borrow().hand.unwrap(), &s.borrow().flop.unwrap().0);
         let s_clone = s.clone();
+        let (g, ss) = (g.clone(), ss.clone());
         let answer_btn = document().query_selector( ".flop" ).unwrap().unwrap();
-        answer_btn.add_event_listener( move |_: ClickEvent| {
+        answer_btn.add_event_listener( move |e: ClickEvent| {
+            let called = match call_or_fold(e) {
+                Some(called) => called,
+                None => return,
+            };
             remove(".flop");
-            s_clone.borrow_mut().answer();
+            let decision = s_clone.borrow().decide(index, Street::Flop, called);
+            ss.borrow_mut().decisions.push(decision);
+            let range = g.borrow().range.clone().unwrap_or_else(|| REVIEW_RANGE.parse().unwrap());
+            s_clone.borrow_mut().answer(Street::Flop, &range);
             s_clone.borrow_mut().turn();
+            {
+                let s = s_clone.borrow();
+                let mut board = s.flop.unwrap().0.to_vec();
+                board.push(s.turn.unwrap());
+                g.borrow_mut().deal(&s.hand.unwrap(), &board);
+            }
 
-            let sc = s_clone.clone();
+            let (sc, ss) = (s_clone.clone(), ss.clone());
             let ans_btn = document().query_selector( ".turn" ).unwrap().unwrap();
-            ans_btn.add_event_listener( move |_: ClickEvent| {
+            ans_btn.add_event_listener( move |e: ClickEvent| {
+                let called = match call_or_fold(e) {
+                    Some(called) => called,
+                    None => return,
+                };
                 remove(".turn");
-                sc.borrow_mut().answer();
+                let decision = sc.borrow().decide(index, Street::Turn, called);
+                ss.borrow_mut().decisions.push(decision);
+                sc.borrow_mut().answer(Street::Turn, &range);
+                let note = sc.borrow().note.clone();
+                if let Some(note) = note {
+                    append_txt(&format!("<div class='answer'>Coach: {}</div>", note));
+                }
             });
         });
     });
+    let (c, sc) = (config.clone(), score.clone());
+    let quiz_btn = document().query_selector( "#quiz" ).unwrap().unwrap();
+    quiz_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        let mut s = Scenario::new(&c);
+        let quiz = s.quiz();
+        ask(quiz, c.clone(), sc.clone(), move |quiz| s.reveal(quiz));
+    });
+    let (c, g) = (config.clone(), grid.clone());
+    let preflop_btn = document().query_selector( "#preflop" ).unwrap().unwrap();
+    preflop_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        let s = Preflop::new(&c, g.borrow().range.clone());
+        g.borrow_mut().deal(&s.hand, &[]);
+        s.show();
+        let answer_btn = document().query_selector( ".preflop" ).unwrap().unwrap();
+        answer_btn.add_event_listener( move |_: ClickEvent| {
+            remove(".preflop");
+            s.answer();
+        });
+    });
+    let c = config.clone();
+    let hilo_btn = document().query_selector( "#hilo" ).unwrap().unwrap();
+    hilo_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        let s = HiLo::new(&c);
+        s.show();
+        let answer_btn = document().query_selector( ".hilo" ).unwrap().unwrap();
+        answer_btn.add_event_listener( move |_: ClickEvent| {
+            remove(".hilo");
+            s.answer();
+        });
+    });
+    let (c, g) = (config.clone(), grid.clone());
+    let review_btn = document().query_selector( "#review" ).unwrap().unwrap();
+    review_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        append_txt("Paste hand histories in the PokerStars format to grade your calls and folds");
+        append_txt("
+        <div class='review'>
+            <textarea rows='12' cols='80'></textarea>
+            <button>Review</button>
+        </div>
+        ");
+        let (c, g) = (c.clone(), g.clone());
+        let submit = document().query_selector( ".review button" ).unwrap().unwrap();
+        submit.add_event_listener( move |_: ClickEvent| {
+            let text = js! {
+                return document.querySelector(".review textarea").value;
+            }.into_string().unwrap_or_default();
+            remove(".review");
+            review(&text, g.borrow().range.clone(), &c);
+        });
+    });
+    let (c, queue) = (config.clone(), imported.clone());
+    let session_btn = document().query_selector( "#session" ).unwrap().unwrap();
+    session_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        show_session(&session.borrow(), c.clone(), queue.clone());
+    });
+    let c = config.clone();
+    let play_btn = document().query_selector( "#play" ).unwrap().unwrap();
+    play_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        append_txt("Heads-up, 100 big blinds each, you have the button");
+        let game = Game::new(&c, [c.big_blind * 100.; 2]);
+        play(Rc::new(RefCell::new(game)), c.clone());
+    });
+    let c = config.clone();
+    let packs_btn = document().query_selector( "#packs" ).unwrap().unwrap();
+    packs_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        choose_pack(c.clone(), imported.clone());
+    });
+    let range_btn = document().query_selector( "#range" ).unwrap().unwrap();
+    range_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        edit_range(grid.clone());
+    });
+    let drill_btn = document().query_selector( "#drill" ).unwrap().unwrap();
+    drill_btn.add_event_listener( move |_: ClickEvent| {
+        clear_txt();
+        let drill = Drill::rand_drill(&config);
+        append_txt(&drill.describe(&config));
+        let c = config.clone();
+        ask(drill.quiz(), config.clone(), score.clone(), move |_| {
+            append_txt(&format!("<div class='answer'>{}</div>", drill.formula(&c)));
+        });
+    });
     stdweb::event_loop();
 }
+
+mod tests {
+    #[test]
+    fn test_short_deck_outs() {
+        use config::Config;
+        use session::ScenarioRecord;
+        use super::Scenario;
+
+        let config = Config { short_deck: true, ..Config::default() };
+        let mut scenario = Scenario::from_record(&config, &ScenarioRecord {
+            pot: 10.,
+            bet: 5.,
+            hand: "AhKh".parse().unwrap(),
+            flop: "Qh7h6c".parse().unwrap(),
+            turn: None,
+            outs: None,
+            equity: None,
+            turn_equity: None,
+        });
+        let dealt = (scenario.flop.unwrap(), scenario.hand.unwrap(), "8d".parse().unwrap());
+        scenario.set_draw(dealt, 5);
+        // five hearts and the three other nines for A6789
+        assert_eq!(Some(8), scenario.outs);
+    }
+
+    #[test]
+    fn test_preflop_grid() {
+        use config::Config;
+        use range::HandClass;
+        use super::Preflop;
+
+        let mut preflop = Preflop::new(&Config::default(), Some("AA, KQs".parse().unwrap()));
+        preflop.hand = "7h2c".parse().unwrap();
+        let html = preflop.grid().html();
+        let cell = |class: &str| {
+            let i = class.parse::<HandClass>().unwrap().index();
+            let start = html.find(&format!("data-index='{}'", i)).unwrap();
+            html[start..start + html[start..].find("</td>").unwrap()].to_string()
+        };
+        assert!(cell("AA").contains("opacity: 1.00"));
+        assert!(cell("KQs").contains("opacity: 1.00"));
+        assert!(cell("KQo").contains("opacity: 0.25"));
+        assert!(cell("72o").contains("opacity: 0.25"));
+    }
+}
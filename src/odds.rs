@@ -0,0 +1,101 @@
+//! Conversions between the ways players talk about the price of a call:
+//! "x to 1" ratios, percentages and counted outs.
+
+/// Break-even equity when the pot lays `ratio` to 1
+pub fn ratio_to_equity(ratio: f32) -> f32 {
+    1. / (ratio + 1.)
+}
+
+/// The "x to 1" against an event happening with probability `equity`
+pub fn equity_to_ratio(equity: f32) -> f32 {
+    (1. - equity) / equity
+}
+
+/// Exact chance that one of `outs` cards comes among the next
+/// `cards_to_come` cards dealt from `unseen`, certain when every unseen
+/// card is an out
+pub fn outs_to_equity(outs: u8, unseen: u8, cards_to_come: u8) -> f32 {
+    let outs = outs.min(unseen);
+    let mut miss = 1.;
+    for i in 0..cards_to_come.min(unseen) {
+        let left = f32::from(unseen - i);
+        miss *= (left - f32::from(outs)) / left;
+    }
+    1. - miss
+}
+
+/// Outs needed to hit with `equity` on the next card
+pub fn equity_to_outs(equity: f32, unseen: u8) -> f32 {
+    equity * f32::from(unseen)
+}
+
+/// Rule of 2 and 4: each out is worth 2% per card to come
+pub fn rule_of_2_4(outs: u8, cards_to_come: u8) -> f32 {
+    f32::from(outs) * 0.02 * f32::from(cards_to_come)
+}
+
+/// How far the rule of 2 and 4 is from the exact equity
+pub fn rule_of_2_4_error(outs: u8, unseen: u8, cards_to_come: u8) -> f32 {
+    rule_of_2_4(outs, cards_to_come) - outs_to_equity(outs, unseen, cards_to_come)
+}
+
+pub fn format_ratio(ratio: f32) -> String {
+    format!("{:.1} to 1", ratio)
+}
+
+pub fn format_percent(x: f32) -> String {
+    format!("{:.1}%", x * 100.)
+}
+
+/// Reads "3.5 to 1", "7:2", "7/2" or a bare "3.5"
+pub fn parse_ratio(s: &str) -> Option<f32> {
+    let s = s.trim().to_lowercase();
+    let mut parts = s.splitn(2, &[':', '/'][..]).collect::<Vec<_>>();
+    if parts.len() == 1 {
+        parts = s.splitn(2, " to ").collect();
+    }
+    let x = parse_number(parts[0])?;
+    match parts.get(1) {
+        Some(y) => Some(x / parse_number(y)?),
+        None => Some(x),
+    }
+}
+
+/// Reads "18%", "18" or "0.18" as 0.18
+pub fn parse_percent(s: &str) -> Option<f32> {
+    let x = parse_number(s)?;
+    if s.trim().ends_with('%') || x > 1. {
+        Some(x / 100.)
+    } else {
+        Some(x)
+    }
+}
+
+/// Strips currency symbols, separators and percent signs
+pub fn parse_number(s: &str) -> Option<f32> {
+    let s: String = s.chars()
+        .filter(|c| c.is_ascii_digit() || *c == '.' || *c == '-')
+        .collect();
+    s.parse().ok().filter(|x: &f32| x.is_finite())
+}
+
+mod tests {
+    #[test]
+    fn test_conversions() {
+        use super::*;
+
+        assert_eq!(0.2, ratio_to_equity(4.));
+        assert_eq!(4., equity_to_ratio(0.2));
+        assert!((outs_to_equity(9, 47, 1) - 9. / 47.).abs() < 1e-6);
+        assert!((outs_to_equity(9, 47, 2) - 0.3497).abs() < 1e-4);
+        assert_eq!(1., outs_to_equity(50, 47, 2));
+        assert_eq!(0., outs_to_equity(0, 1, 3));
+        assert!((equity_to_outs(9. / 47., 47) - 9.).abs() < 1e-5);
+        assert!((rule_of_2_4(9, 2) - 0.36).abs() < 1e-6);
+        assert!((rule_of_2_4_error(9, 47, 2) - 0.0103).abs() < 1e-4);
+        assert_eq!("3.5 to 1", format_ratio(3.5));
+        assert_eq!("19.1%", format_percent(9. / 47.));
+        assert_eq!(Some(3.5), parse_ratio("7:2"));
+        assert_eq!(Some(0.18), parse_percent("18%"));
+    }
+}
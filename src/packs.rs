@@ -0,0 +1,187 @@
+//! Scenario packs: hands a coach wrote down to teach a spot, each with the
+//! exact cards, pot, bet and a note shown after the answer.
+//!
+//! A pack is TOML, or JSON if it starts with `{`:
+//!
+//! ```toml
+//! name = "Drawing on the flop"
+//! description = "Flush and straight draws facing a bet"
+//!
+//! [[hands]]
+//! hand = "AhKh"
+//! flop = "2h7h9c"
+//! turn = "Ts"        # optional, dealt to miss the draw when left out
+//! pot = 20.0
+//! bet = 5.0
+//! outs = 9
+//! note = "A quarter pot bet needs only 17%."
+//! ```
+
+use rand::prelude::*;
+use serde_json;
+use toml;
+
+use card::{Card, CardRepr, Flop, Hand};
+use config::Config;
+use deck::Deck;
+use evaluate;
+use odds;
+use session::ScenarioRecord;
+use tables::Category;
+
+/// Packs built into the trainer, offered besides any on the page
+pub const BUILT_IN: [&str; 1] = [include_str!("../static/packs/draws.toml")];
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct PackHand {
+    pub hand: Hand,
+    pub flop: Flop,
+    pub turn: Option<Card>,
+    pub pot: f32,
+    pub bet: f32,
+    pub outs: u8,
+    #[serde(default)]
+    pub note: String,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct Pack {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub hands: Vec<PackHand>,
+}
+
+impl PackHand {
+    /// The deck left after taking this hand's cards out of it. Fails on a
+    /// card dealt twice or missing from the deck, like a five in short deck.
+    fn deck(&self, config: &Config) -> Result<Deck, String> {
+        let cards: Vec<Card> = self.hand.0.iter().chain(&self.flop.0).chain(&self.turn).cloned().collect();
+        Deck::without(config, &cards)
+    }
+
+    fn validate(&self, config: &Config) -> Result<(), String> {
+        self.deck(config)?;
+        if self.pot <= 0. || self.bet <= 0. {
+            return Err("pot and bet must be positive".to_owned());
+        }
+        if self.outs >= config.deck_size() - 5 {
+            return Err(format!("{} outs is more than the unseen cards", self.outs));
+        }
+        Ok(())
+    }
+
+    /// Whether `turn` leaves the draw open: it doesn't give a straight or
+    /// better to a hand that had less on the flop
+    fn misses(&self, config: &Config, turn: Card) -> bool {
+        let eval = if config.short_deck { evaluate::evaluate_short_deck } else { evaluate::evaluate5 };
+        let [a, b] = self.hand.0;
+        let [c, d, e] = self.flop.0;
+        let cards = [a.to_int(), b.to_int(), c.to_int(), d.to_int(), e.to_int(), turn.to_int()];
+        let made = |rank| evaluate::category(rank) >= Category::Straight;
+        if made(eval(cards[0], cards[1], cards[2], cards[3], cards[4])) {
+            return true;
+        }
+        (0..5).all(|skip| {
+            let mut five = [cards[5]; 5];
+            for (id, (_, &card)) in five.iter_mut().zip(cards[..5].iter().enumerate().filter(|&(i, _)| i != skip)) {
+                *id = card;
+            }
+            !made(eval(five[0], five[1], five[2], five[3], five[4]))
+        })
+    }
+
+    /// The hand as a scenario to play, with the turn dealt if left out
+    pub fn record(&self, config: &Config) -> ScenarioRecord {
+        let turn = match self.turn {
+            Some(turn) => turn,
+            None => {
+                // like the built-in scenarios, the turn misses the draw
+                let deck = self.deck(config).unwrap();
+                let misses: Vec<Card> = deck.cards().iter().cloned().filter(|&turn| self.misses(config, turn)).collect();
+                *thread_rng().choose(&misses).unwrap()
+            }
+        };
+        let unseen = config.deck_size() - 5;
+        ScenarioRecord {
+            pot: self.pot,
+            bet: self.bet,
+            hand: self.hand,
+            flop: self.flop,
+            turn: Some(turn),
+            outs: Some(self.outs),
+            equity: Some(odds::outs_to_equity(self.outs, unseen, 1)),
+            turn_equity: Some(odds::outs_to_equity(self.outs, unseen - 1, 1)),
+        }
+    }
+}
+
+impl Pack {
+    /// Reads a pack in either format and checks every hand against a fresh
+    /// deck for `config`
+    pub fn parse(s: &str, config: &Config) -> Result<Self, String> {
+        let pack: Pack = if s.trim_start().starts_with('{') {
+            serde_json::from_str(s).map_err(|e| e.to_string())?
+        } else {
+            toml::from_str(s).map_err(|e| e.to_string())?
+        };
+        if pack.hands.is_empty() {
+            return Err(format!("pack {} has no hands", pack.name));
+        }
+        for (i, hand) in pack.hands.iter().enumerate() {
+            hand.validate(config).map_err(|e| format!("{}, hand {}: {}", pack.name, i + 1, e))?;
+        }
+        Ok(pack)
+    }
+
+    /// The hands as scenarios with their notes, in the pack's order or
+    /// shuffled
+    pub fn records(&self, config: &Config, shuffled: bool) -> Vec<(ScenarioRecord, String)> {
+        let mut hands = self.hands.clone();
+        if shuffled {
+            thread_rng().shuffle(&mut hands);
+        }
+        hands.iter().map(|h| (h.record(config), h.note.clone())).collect()
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_packs() {
+        use card::{Card, Rank, Suit};
+        use config::Config;
+        use packs::{Pack, BUILT_IN};
+
+        let config = Config::default();
+        let pack = Pack::parse(BUILT_IN[0], &config).unwrap();
+        assert_eq!(4, pack.hands.len());
+        let records = pack.records(&config, true);
+        assert_eq!(4, records.len());
+        assert!(records.iter().all(|(r, note)| !note.is_empty() && r.turn.is_some() && r.equity.is_some()));
+        // QsJs on Ts9s2h: no spade, king or eight on the turn
+        let open_ender = &pack.hands[3];
+        assert!((open_ender.record(&config).equity.unwrap() - 15. / 47.).abs() < 1e-6);
+        for _ in 0..100 {
+            let Card(suit, rank) = open_ender.record(&config).turn.unwrap();
+            assert!(suit != Suit::Spade && rank != Rank::King && rank != Rank::Eight);
+        }
+
+        let hand = |cards: &str| format!("
+            name = \"test\"
+            [[hands]]
+            {}
+            pot = 10.0
+            bet = 5.0
+            outs = 9
+        ", cards);
+        assert!(Pack::parse(&hand("hand = \"AhKh\"\nflop = \"2h7h9c\""), &config).is_ok());
+        // the ace of hearts twice
+        assert!(Pack::parse(&hand("hand = \"AhKh\"\nflop = \"2h7hAh\""), &config).is_err());
+        assert!(Pack::parse(&hand("hand = \"AhKh\"\nflop = \"2h7h9c\"\nturn = \"Kh\""), &config).is_err());
+        assert!(Pack::parse(&hand("hand = \"AhKx\"\nflop = \"2h7h9c\""), &config).is_err());
+        let short = Config { short_deck: true, ..Config::default() };
+        assert!(Pack::parse(&hand("hand = \"AhKh\"\nflop = \"2h7h9c\""), &short).is_err());
+        assert!(Pack::parse(r#"{"name": "json", "hands": [{"hand": "AhKh", "flop": "Th7h9c",
+            "pot": 10.0, "bet": 5.0, "outs": 9}]}"#, &short).is_ok());
+    }
+}
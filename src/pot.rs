@@ -0,0 +1,52 @@
+//! The pot a bet goes into and what calling it is worth after rake.
+
+use config::Config;
+use rake::{Ev, Rake};
+
+pub struct Pot {
+    pub size: f32,
+    pub rake: Rake,
+    pub flop_dealt: bool,
+}
+
+impl Pot {
+    pub fn new(size: f32) -> Self {
+        Pot::with_rake(size, Rake::none())
+    }
+    pub fn with_rake(size: f32, rake: Rake) -> Self {
+        Pot {
+            size,
+            rake,
+            flop_dealt: true,
+        }
+    }
+    pub fn rand_pot(config: &Config) -> Self {
+        Pot::with_rake(config.rand_pot_size(), config.rake)
+    }
+    pub fn rand_bet(&self, config: &Config) -> f32 {
+        self.size * config.rand_bet_ratio()
+    }
+    /// Rake taken if `bet` is called and the hand goes to showdown
+    pub fn rake(&self, bet: f32) -> f32 {
+        self.rake.taken(self.size + bet * 2., self.flop_dealt)
+    }
+    pub fn odds(&self, bet: f32) -> f32 {
+        bet / (bet + self.size - self.rake(bet))
+    }
+    /// Equity at which calling `bet` breaks even
+    pub fn required_equity(&self, bet: f32) -> f32 {
+        let odds = self.odds(bet);
+        odds / (1. + odds)
+    }
+    /// EV of calling `bet` with `equity`, with and without rake
+    pub fn ev(&self, bet: f32, equity: f32) -> Ev {
+        let rake = self.rake(bet);
+        let win = self.size + bet;
+        let lose = bet * (1. - equity);
+        Ev {
+            gross: win * equity - lose,
+            net: (win - rake) * equity - lose,
+            rake,
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use std::fmt::{self, Display};
+
+use config::Config;
+use odds;
+
+/// What the player has to type in
+pub trait Ask: Copy {
+    fn prompt(&self) -> &'static str;
+
+    /// Reads the player's answer in the same units as `Quiz::expected`
+    fn parse(&self, input: &str) -> Option<f32>;
+
+    /// Formats an answer the way `parse` reads it
+    fn show(&self, x: f32, config: &Config) -> String;
+}
+
+/// Questions about calling a bet
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Question {
+    /// Equity needed to break even on a call, "18%" or "0.18"
+    RequiredEquity,
+    /// Reward to risk, "3.5 to 1" or "3.5:1"
+    PotOdds,
+    /// Cards that improve the hand to the winner
+    Outs,
+    /// Expected value of calling
+    Ev,
+}
+
+impl Question {
+    /// One of the questions about calling a bet
+    pub fn get(i: u64) -> Question {
+        use self::Question::*;
+        match i % 4 {
+            0 => RequiredEquity,
+            1 => PotOdds,
+            2 => Outs,
+            3 => Ev,
+            _ => unimplemented!(),
+        }
+    }
+}
+
+impl Ask for Question {
+    fn prompt(&self) -> &'static str {
+        use self::Question::*;
+        match self {
+            RequiredEquity => "What equity do you need to call?",
+            PotOdds => "What pot odds are you getting (x to 1)?",
+            Outs => "How many outs do you have?",
+            Ev => "What is the EV of calling?",
+        }
+    }
+
+    fn parse(&self, input: &str) -> Option<f32> {
+        use self::Question::*;
+        match self {
+            RequiredEquity => odds::parse_percent(input),
+            PotOdds => odds::parse_ratio(input),
+            Outs | Ev => odds::parse_number(input),
+        }
+    }
+
+    fn show(&self, x: f32, config: &Config) -> String {
+        use self::Question::*;
+        match self {
+            RequiredEquity => odds::format_percent(x),
+            PotOdds => odds::format_ratio(x),
+            Outs => format!("{}", x),
+            Ev => config.money(x),
+        }
+    }
+}
+
+/// A question with its answer.
+///
+/// Answers within `tolerance` score full credit, credit then falls off
+/// linearly and is zero at `PARTIAL` times the tolerance.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Quiz<Q = Question> {
+    pub question: Q,
+    pub expected: f32,
+    pub tolerance: f32,
+}
+
+const PARTIAL: f32 = 4.;
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Grade {
+    pub answer: f32,
+    pub expected: f32,
+    /// Between 0 and 1
+    pub score: f32,
+}
+
+impl<Q: Ask> Quiz<Q> {
+    pub fn new(question: Q, expected: f32, tolerance: f32) -> Self {
+        Self {
+            question,
+            expected,
+            tolerance,
+        }
+    }
+
+    /// `None` if the input can't be read as an answer
+    pub fn grade(&self, input: &str) -> Option<Grade> {
+        let answer = self.question.parse(input)?;
+        let err = (answer - self.expected).abs();
+        let score = if err <= self.tolerance {
+            1.
+        } else {
+            let width = self.tolerance * (PARTIAL - 1.);
+            (1. - (err - self.tolerance) / width).max(0.)
+        };
+        Some(Grade {
+            answer,
+            expected: self.expected,
+            score,
+        })
+    }
+}
+
+impl Display for Grade {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        if self.score == 1. {
+            write!(fmt, "Correct!")
+        } else if self.score > 0. {
+            write!(fmt, "Close, {:.0}% credit", self.score * 100.)
+        } else {
+            write!(fmt, "Wrong")
+        }
+    }
+}
+
+/// Running total over a session
+#[derive(PartialEq, Debug, Default, Copy, Clone)]
+pub struct Score {
+    pub points: f32,
+    pub questions: u32,
+}
+
+impl Score {
+    pub fn add(&mut self, grade: &Grade) {
+        self.points += grade.score;
+        self.questions += 1;
+    }
+}
+
+impl Display for Score {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        write!(fmt, "Score: {:.2} / {}", self.points, self.questions)
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_parse_answers() {
+        use super::Ask;
+        use super::Question::*;
+
+        assert_eq!(Some(0.18), RequiredEquity.parse("18%"));
+        assert_eq!(Some(0.18), RequiredEquity.parse("18"));
+        assert_eq!(Some(0.18), RequiredEquity.parse(" 0.18 "));
+        assert_eq!(Some(3.5), PotOdds.parse("3.5 to 1"));
+        assert_eq!(Some(3.5), PotOdds.parse("7:2"));
+        assert_eq!(Some(3.5), PotOdds.parse("3.5"));
+        assert_eq!(Some(9.), Outs.parse("9"));
+        assert_eq!(Some(-12.5), Ev.parse("$-12.50"));
+        assert_eq!(None, Ev.parse("call"));
+    }
+
+    #[test]
+    fn test_grade() {
+        use super::{Quiz, Question};
+
+        let quiz = Quiz::new(Question::Outs, 9., 0.5);
+        assert_eq!(1., quiz.grade("9").unwrap().score);
+        assert_eq!(0.5, quiz.grade("10.25").unwrap().score);
+        assert_eq!(0., quiz.grade("4").unwrap().score);
+        assert_eq!(None, quiz.grade("nine"));
+    }
+}
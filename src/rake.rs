@@ -0,0 +1,54 @@
+/// House rake taken from a pot that reaches showdown.
+#[derive(Serialize, Deserialize, PartialEq, Debug, Copy, Clone)]
+pub struct Rake {
+    /// Fraction of the pot taken, e.g. 0.05 for 5%
+    pub percent: f32,
+    /// Most the house takes from a single pot
+    pub cap: f32,
+    /// Hands that end before the flop are not raked
+    pub no_flop_no_drop: bool,
+}
+
+impl Rake {
+    pub fn new(percent: f32, cap: f32, no_flop_no_drop: bool) -> Self {
+        Self {
+            percent,
+            cap,
+            no_flop_no_drop,
+        }
+    }
+
+    pub fn none() -> Self {
+        Self::new(0., 0., true)
+    }
+
+    /// Amount taken from a final pot of `pot`
+    pub fn taken(&self, pot: f32, flop_dealt: bool) -> f32 {
+        if self.no_flop_no_drop && !flop_dealt {
+            return 0.;
+        }
+        (pot * self.percent).min(self.cap)
+    }
+}
+
+/// Expected value of a call, before and after rake.
+#[derive(PartialEq, Debug, Copy, Clone)]
+pub struct Ev {
+    pub gross: f32,
+    pub net: f32,
+    pub rake: f32,
+}
+
+mod tests {
+    #[test]
+    fn test_rake() {
+        use super::Rake;
+
+        let rake = Rake::new(0.05, 3., true);
+        assert_eq!(1., rake.taken(20., true));
+        assert_eq!(3., rake.taken(200., true));
+        assert_eq!(0., rake.taken(200., false));
+        assert_eq!(3., Rake::new(0.05, 3., false).taken(200., false));
+        assert_eq!(0., Rake::none().taken(200., true));
+    }
+}
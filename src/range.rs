@@ -0,0 +1,291 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use card::{Card, Hand, Rank, Suit};
+
+/// One of the 169 starting hand classes, e.g. "AKs", "QQ" or "T9o"
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct HandClass {
+    pub high: Rank,
+    pub low: Rank,
+    pub suited: bool,
+}
+
+impl HandClass {
+    /// Pairs are never suited
+    pub fn new(a: Rank, b: Rank, suited: bool) -> Self {
+        let (high, low) = if a.value() >= b.value() { (a, b) } else { (b, a) };
+        Self {
+            high,
+            low,
+            suited: suited && high != low,
+        }
+    }
+
+    pub fn of(hand: &Hand) -> Self {
+        let Hand([a, b]) = *hand;
+        HandClass::new(a.1, b.1, a.0 == b.0)
+    }
+
+    /// Position in the 13x13 grid read row by row from the top left,
+    /// aces first, suited hands above the diagonal
+    pub fn index(&self) -> usize {
+        let high = 12 - self.high.value() as usize;
+        let low = 12 - self.low.value() as usize;
+        if self.suited {
+            high * 13 + low
+        } else {
+            low * 13 + high
+        }
+    }
+
+    pub fn from_index(i: usize) -> Self {
+        let row = Rank::get(12 - (i / 13) as u64);
+        let col = Rank::get(12 - (i % 13) as u64);
+        HandClass::new(row, col, i / 13 < i % 13)
+    }
+
+    pub fn all() -> Vec<HandClass> {
+        (0..169).map(HandClass::from_index).collect()
+    }
+
+    pub fn is_pair(&self) -> bool {
+        self.high == self.low
+    }
+
+    /// 6 for pairs, 4 for suited and 12 for offsuit hands
+    pub fn combos(&self) -> Vec<Hand> {
+        let mut ret = Vec::new();
+        for i in 0..4 {
+            for j in 0..4 {
+                let keep = if self.is_pair() {
+                    i < j
+                } else {
+                    (i == j) == self.suited
+                };
+                if keep {
+                    ret.push(Hand([Card(Suit::get(i), self.high), Card(Suit::get(j), self.low)]));
+                }
+            }
+        }
+        ret
+    }
+}
+
+impl Display for HandClass {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        if self.is_pair() {
+            write!(fmt, "{}{}", self.high, self.low)
+        } else {
+            write!(fmt, "{}{}{}", self.high, self.low, if self.suited { "s" } else { "o" })
+        }
+    }
+}
+
+/// Reads "AK", "AKs", "AKo" or "QQ", `None` meaning both suited and offsuit
+fn parse_class(s: &str) -> Result<(Rank, Rank, Option<bool>), String> {
+    let chars: Vec<char> = s.trim().chars().collect();
+    let err = || format!("bad hand class: {}", s);
+    if chars.len() < 2 || chars.len() > 3 {
+        return Err(err());
+    }
+    let a = Rank::from_char(chars[0]).ok_or_else(err)?;
+    let b = Rank::from_char(chars[1]).ok_or_else(err)?;
+    let suited = match chars.get(2) {
+        None => None,
+        Some('s') | Some('S') => Some(true),
+        Some('o') | Some('O') => Some(false),
+        _ => return Err(err()),
+    };
+    if a == b && suited.is_some() {
+        return Err(err());
+    }
+    let (a, b) = if a.value() >= b.value() { (a, b) } else { (b, a) };
+    Ok((a, b, suited))
+}
+
+impl FromStr for HandClass {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match parse_class(s)? {
+            (a, b, _) if a == b => Ok(HandClass::new(a, b, false)),
+            (a, b, Some(suited)) => Ok(HandClass::new(a, b, suited)),
+            _ => Err(format!("say suited or offsuit: {}", s)),
+        }
+    }
+}
+
+/// Weight between 0 and 1 for each hand class.
+///
+/// Written in the usual notation, e.g. "QQ+, A2s-A5s, KTs+, AKo, 76s:0.5".
+#[derive(PartialEq, Debug, Clone)]
+pub struct Range {
+    /// By `HandClass::index`
+    pub weights: Vec<f32>,
+}
+
+impl Range {
+    pub fn empty() -> Self {
+        Self { weights: vec![0.; 169] }
+    }
+
+    pub fn get(&self, class: HandClass) -> f32 {
+        self.weights[class.index()]
+    }
+
+    pub fn set(&mut self, class: HandClass, weight: f32) {
+        self.weights[class.index()] = weight;
+    }
+
+    pub fn classes(&self) -> Vec<(HandClass, f32)> {
+        HandClass::all().into_iter()
+            .map(|c| (c, self.get(c)))
+            .filter(|&(_, w)| w > 0.)
+            .collect()
+    }
+
+    pub fn combos(&self) -> Vec<(Hand, f32)> {
+        self.classes().into_iter()
+            .flat_map(|(c, w)| c.combos().into_iter().map(move |h| (h, w)))
+            .collect()
+    }
+
+    fn add(&mut self, item: &str, weight: f32) -> Result<(), String> {
+        let (a, b, suited, upto) = if let Some(item) = item.strip_suffix('+') {
+            let (a, b, suited) = parse_class(item)?;
+            // pairs go up to aces, other hands raise the kicker
+            let top = if a == b { Rank::Ace } else { Rank::get(a.value() - 1) };
+            (a, b, suited, (a, top))
+        } else if let Some(i) = item.find('-') {
+            let (a, b, suited) = parse_class(&item[..i])?;
+            let (c, d, other) = parse_class(&item[i + 1..])?;
+            if suited != other || (a == b) != (c == d) || (a != b && a != c) {
+                return Err(format!("bad range: {}", item));
+            }
+            (a, b, suited, (c, d))
+        } else {
+            let (a, b, suited) = parse_class(item)?;
+            (a, b, suited, (a, b))
+        };
+        let pair = a == b;
+        let (from, to) = (b.value(), upto.1.value());
+        for v in from.min(to)..=from.max(to) {
+            let r = Rank::get(v);
+            let classes = if pair {
+                vec![HandClass::new(r, r, false)]
+            } else {
+                match suited {
+                    Some(s) => vec![HandClass::new(a, r, s)],
+                    None => vec![HandClass::new(a, r, true), HandClass::new(a, r, false)],
+                }
+            };
+            for c in classes {
+                self.set(c, weight);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Range {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut range = Range::empty();
+        for token in s.split(',').map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            let (item, weight) = match token.find(':') {
+                Some(i) => {
+                    let w: f32 = token[i + 1..].trim().parse().map_err(|_| format!("bad weight: {}", token))?;
+                    if !(0. ..=1.).contains(&w) {
+                        return Err(format!("weight out of range: {}", token));
+                    }
+                    (token[..i].trim(), w)
+                }
+                None => (token, 1.),
+            };
+            range.add(item, weight)?;
+        }
+        Ok(range)
+    }
+}
+
+impl Display for Range {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        // runs of pairs, then of kickers under each high card, with one weight
+        let mut runs: Vec<(HandClass, HandClass, bool)> = Vec::new();
+        let mut push = |classes: Vec<HandClass>| {
+            let mut i = 0;
+            while i < classes.len() {
+                let w = self.get(classes[i]);
+                let mut j = i;
+                while j + 1 < classes.len() && self.get(classes[j + 1]) == w {
+                    j += 1;
+                }
+                if w > 0. {
+                    runs.push((classes[i], classes[j], i == 0));
+                }
+                i = j + 1;
+            }
+        };
+        push((0..13).rev().map(|v| HandClass::new(Rank::get(v), Rank::get(v), false)).collect());
+        for &suited in &[true, false] {
+            for high in (1..13).rev() {
+                let classes = (0..high).rev()
+                    .map(|low| HandClass::new(Rank::get(high), Rank::get(low), suited))
+                    .collect();
+                push(classes);
+            }
+        }
+
+        let mut first = true;
+        for (start, end, top) in runs {
+            if !first {
+                write!(fmt, ", ")?;
+            }
+            first = false;
+            if start == end {
+                write!(fmt, "{}", start)?;
+            } else if top {
+                write!(fmt, "{}+", end)?;
+            } else {
+                write!(fmt, "{}-{}", start, end)?;
+            }
+            let w = self.get(start);
+            if w < 1. {
+                write!(fmt, ":{}", w)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_hand_class() {
+        use super::HandClass;
+
+        for i in 0..169 {
+            assert_eq!(i, HandClass::from_index(i).index());
+        }
+        let aks: HandClass = "AKs".parse().unwrap();
+        assert_eq!(1, aks.index());
+        assert_eq!(4, aks.combos().len());
+        assert_eq!(12, "T9o".parse::<HandClass>().unwrap().combos().len());
+        assert_eq!(6, "22".parse::<HandClass>().unwrap().combos().len());
+        assert_eq!("AKo", format!("{}", HandClass::from_index(13)));
+        assert_eq!(aks, HandClass::of(&"KhAh".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_range_notation() {
+        use super::Range;
+
+        let range: Range = "QQ+, 22-44, A2s-A5s, KTs+, AK, 76s:0.5".parse().unwrap();
+        assert_eq!(3 * 6 + 3 * 6 + 4 * 4 + 3 * 4 + 16 + 4, range.combos().len());
+        assert_eq!("QQ+, 44-22, AKs, A5s-A2s, KTs+, 76s:0.5, AKo", format!("{}", range));
+        assert_eq!(range, format!("{}", range).parse().unwrap());
+        assert!("AKx".parse::<Range>().is_err());
+        assert!("AK:2".parse::<Range>().is_err());
+    }
+}
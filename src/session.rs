@@ -0,0 +1,178 @@
+//! Training sessions as JSON for sharing scenarios and as CSV for
+//! spreadsheets. Cards are written in text notation, e.g. "AhKh".
+//!
+//! The JSON looks like
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "scenarios": [{ "pot": 20.0, "bet": 10.0, "hand": "AhKh",
+//!                   "flop": "2h7h9c", "turn": "Ts", "outs": 9,
+//!                   "equity": 0.19, "turn_equity": 0.196 }],
+//!   "decisions": [{ "scenario": 0, "street": "flop", "action": "call",
+//!                   "pot": 20.0, "bet": 10.0, "equity": 0.19,
+//!                   "required": 0.25, "ev": -2.4, "correct": false }]
+//! }
+//! ```
+//!
+//! Fields may be added, but only along with a new `version`.
+
+use serde_json;
+
+use card::{cards_to_text, Card, Flop, Hand};
+use history::{Action, Street};
+
+pub const VERSION: u32 = 1;
+
+/// A flop spot with what hero needs to know to call
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct ScenarioRecord {
+    pub pot: f32,
+    pub bet: f32,
+    pub hand: Hand,
+    pub flop: Flop,
+    pub turn: Option<Card>,
+    pub outs: Option<u8>,
+    /// Chance to hit with one card to come, on the flop and on the turn
+    pub equity: Option<f32>,
+    pub turn_equity: Option<f32>,
+}
+
+impl ScenarioRecord {
+    /// Hand, flop and turn, in that order
+    pub fn cards(&self) -> Vec<Card> {
+        self.hand.0.iter().chain(&self.flop.0).chain(&self.turn).cloned().collect()
+    }
+}
+
+/// A call or fold graded against the pot odds
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct DecisionRecord {
+    /// Index into `Session::scenarios`
+    pub scenario: usize,
+    pub street: Street,
+    pub action: Action,
+    pub pot: f32,
+    pub bet: f32,
+    pub equity: f32,
+    pub required: f32,
+    /// Net EV of calling
+    pub ev: f32,
+    pub correct: bool,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Debug, Clone)]
+pub struct Session {
+    pub version: u32,
+    pub scenarios: Vec<ScenarioRecord>,
+    #[serde(default)]
+    pub decisions: Vec<DecisionRecord>,
+}
+
+impl Default for Session {
+    fn default() -> Self {
+        Self {
+            version: VERSION,
+            scenarios: Vec::new(),
+            decisions: Vec::new(),
+        }
+    }
+}
+
+impl Session {
+    /// Adds a scenario, returning its index for `DecisionRecord::scenario`
+    pub fn add(&mut self, scenario: ScenarioRecord) -> usize {
+        self.scenarios.push(scenario);
+        self.scenarios.len() - 1
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).unwrap()
+    }
+
+    pub fn from_json(s: &str) -> Result<Self, String> {
+        let session: Session = serde_json::from_str(s).map_err(|e| e.to_string())?;
+        if session.version > VERSION {
+            return Err(format!("session version {} is newer than {}", session.version, VERSION));
+        }
+        if let Some(d) = session.decisions.iter().find(|d| d.scenario >= session.scenarios.len()) {
+            return Err(format!("decision on missing scenario {}", d.scenario));
+        }
+        for (i, scenario) in session.scenarios.iter().enumerate() {
+            // played from these as they are, the session doesn't say
+            // which cards are outs to work them out again
+            if scenario.turn.is_none() || scenario.outs.is_none()
+                || scenario.equity.is_none() || scenario.turn_equity.is_none()
+            {
+                return Err(format!("scenario {} needs its turn, outs and equities", i));
+            }
+            let cards = scenario.cards();
+            if let Some((_, card)) = cards.iter().enumerate().find(|&(j, c)| cards[..j].contains(c)) {
+                return Err(format!("scenario {}: {} is dealt twice", i, card.to_text()));
+            }
+        }
+        Ok(session)
+    }
+
+    /// One row per decision, with the cards seen when it was made
+    pub fn to_csv(&self) -> String {
+        let mut ret = String::from("scenario,street,hand,board,pot,bet,action,equity,required,ev,correct\n");
+        for d in &self.decisions {
+            let s = &self.scenarios[d.scenario];
+            let mut board = s.flop.0.to_vec();
+            if d.street != Street::Flop {
+                board.extend(s.turn);
+            }
+            ret += &format!("{},{},{},{},{:.2},{:.2},{},{:.4},{:.4},{:.2},{}\n",
+                d.scenario, d.street.to_text(), s.hand.to_text(), cards_to_text(&board),
+                d.pot, d.bet, d.action.to_text(), d.equity, d.required, d.ev, d.correct);
+        }
+        ret
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_session() {
+        use history::{Action, Street};
+        use session::*;
+
+        let mut session = Session::default();
+        let scenario = ScenarioRecord {
+            pot: 20.,
+            bet: 10.,
+            hand: "AhKh".parse().unwrap(),
+            flop: "2h7h9c".parse().unwrap(),
+            turn: Some("Ts".parse().unwrap()),
+            outs: Some(9),
+            equity: Some(9. / 47.),
+            turn_equity: Some(9. / 46.),
+        };
+        let i = session.add(scenario);
+        session.decisions.push(DecisionRecord {
+            scenario: i,
+            street: Street::Turn,
+            action: Action::Call,
+            pot: 40.,
+            bet: 20.,
+            equity: 9. / 46.,
+            required: 0.25,
+            ev: -6.5,
+            correct: false,
+        });
+
+        let json = session.to_json();
+        assert!(json.contains("\"hand\": \"AhKh\"") && json.contains("\"street\": \"turn\""));
+        assert_eq!(session, Session::from_json(&json).unwrap());
+        assert!(Session::from_json(&json.replace("AhKh", "AhAh")).is_err());
+        // the turn is already in hero's hand
+        assert!(Session::from_json(&json.replace("\"Ts\"", "\"Kh\"")).is_err());
+        assert!(Session::from_json(&json.replace("\"version\": 1", "\"version\": 2")).is_err());
+        assert!(Session::from_json(&json.replace("\"outs\": 9", "\"outs\": null")).is_err());
+        assert!(Session::from_json(&json.replace("\"turn\": \"Ts\",", "")).is_err());
+
+        let csv = session.to_csv();
+        assert_eq!(2, csv.lines().count());
+        assert_eq!("0,turn,AhKh,2h7h9cTs,40.00,20.00,call,0.1957,0.2500,-6.50,false", csv.lines().nth(1).unwrap());
+    }
+}
@@ -0,0 +1,212 @@
+//! Derives the evaluator's lookup tables from the rules of poker, so the
+//! literals in `evaluate.rs` can be checked and tables for other games
+//! built the same way.
+//!
+//! `cargo run --bin gen_tables` prints them in the form `evaluate.rs` uses.
+
+/// A five card hand's category and the ranks breaking ties inside it,
+/// both larger for better hands. Ranks are 0 for deuces to 12 for aces.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Clone)]
+pub struct HandValue {
+    pub category: Category,
+    pub ranks: Vec<usize>,
+}
+
+#[derive(PartialEq, Eq, PartialOrd, Ord, Debug, Copy, Clone)]
+pub enum Category {
+    HighCard,
+    Pair,
+    TwoPair,
+    Trips,
+    Straight,
+    Flush,
+    FullHouse,
+    Quads,
+    StraightFlush,
+}
+
+/// Value of five cards given how many of each rank they hold
+pub fn hand_value(quinary: &[usize; 13], flush: bool) -> HandValue {
+    use self::Category::*;
+    // groups by size then rank, biggest first
+    let mut groups: Vec<(usize, usize)> = (0..13)
+        .filter(|&r| quinary[r] > 0)
+        .map(|r| (quinary[r], r))
+        .collect();
+    groups.sort_by(|a, b| b.cmp(a));
+    let mut ranks: Vec<usize> = groups.iter().map(|g| g.1).collect();
+
+    let straight = if groups.len() < 5 {
+        None
+    } else if ranks[0] - ranks[4] == 4 {
+        Some(ranks[0])
+    } else if ranks == [12, 3, 2, 1, 0] {
+        // the wheel, five high
+        Some(3)
+    } else {
+        None
+    };
+    if let Some(high) = straight {
+        ranks = vec![high];
+    }
+
+    let category = match (straight.is_some(), flush, groups[0].0, groups.get(1).map(|g| g.0)) {
+        (true, true, _, _) => StraightFlush,
+        (_, _, 4, _) => Quads,
+        (_, _, 3, Some(2)) => FullHouse,
+        (_, true, _, _) => Flush,
+        (true, _, _, _) => Straight,
+        (_, _, 3, _) => Trips,
+        (_, _, 2, Some(2)) => TwoPair,
+        (_, _, 2, _) => Pair,
+        _ => HighCard,
+    };
+    HandValue { category, ranks }
+}
+
+/// Calls `f` with every way to hold `left` cards in the ranks from `i` up
+pub(crate) fn quinaries<F: FnMut(&[usize; 13])>(q: &mut [usize; 13], i: usize, left: usize, f: &mut F) {
+    if i == 13 {
+        if left == 0 {
+            f(q);
+        }
+        return;
+    }
+    for n in 0..=left.min(4) {
+        q[i] = n;
+        quinaries(q, i + 1, left - n, f);
+    }
+    q[i] = 0;
+}
+
+fn bits_to_quinary(bits: usize) -> [usize; 13] {
+    let mut q = [0; 13];
+    for (r, n) in q.iter_mut().enumerate() {
+        *n = bits >> r & 1;
+    }
+    q
+}
+
+/// Every distinct five card hand value, best first, so that a hand's rank
+/// is its position plus one
+pub fn hand_values() -> Vec<HandValue> {
+    let mut values = Vec::new();
+    quinaries(&mut [0; 13], 0, 5, &mut |q| values.push(hand_value(q, false)));
+    for bits in 0..1usize << 13 {
+        if bits.count_ones() == 5 {
+            values.push(hand_value(&bits_to_quinary(bits), true));
+        }
+    }
+    values.sort_by(|a, b| b.cmp(a));
+    values
+}
+
+/// Position plus one of `value` in `hand_values`
+pub fn rank_of(values: &[HandValue], value: &HandValue) -> usize {
+    // best first, so the search runs on the reversed order
+    values.binary_search_by(|v| value.cmp(v)).unwrap() + 1
+}
+
+/// `dp()[n][len][k]`: quinaries of `len + 1` digits summing to `k` that
+/// start with a digit below `n`. Summing these digit by digit numbers the
+/// quinaries of each length in order.
+pub fn dp() -> [[[usize; 8]; 14]; 5] {
+    // ways[len][k]: quinaries of `len` digits summing to `k`
+    let mut ways = [[0; 8]; 14];
+    ways[0][0] = 1;
+    for len in 1..14 {
+        for k in 0..8 {
+            ways[len][k] = (0..=k.min(4)).map(|d| ways[len - 1][k - d]).sum();
+        }
+    }
+    // the last digit is fixed by the sum, so `len` 0 stays empty
+    let mut dp = [[[0; 8]; 14]; 5];
+    for n in 1..5 {
+        for len in 1..14 {
+            for k in 0..8 {
+                dp[n][len][k] = dp[n - 1][len][k] + if k >= n - 1 { ways[len][k - (n - 1)] } else { 0 };
+            }
+        }
+    }
+    dp
+}
+
+/// Rank of the best five card flush for every 13 bit rank set with five
+/// to seven ranks, 0 elsewhere
+pub fn flush() -> Vec<usize> {
+    let values = hand_values();
+    let mut table = vec![0; 1 << 13];
+    for (bits, rank) in table.iter_mut().enumerate() {
+        if !(5..=7).contains(&bits.count_ones()) {
+            continue;
+        }
+        // best of the five rank subsets
+        let mut sub = bits;
+        *rank = usize::MAX;
+        while sub != 0 {
+            if sub.count_ones() == 5 {
+                *rank = (*rank).min(rank_of(&values, &hand_value(&bits_to_quinary(sub), true)));
+            }
+            sub = (sub - 1) & bits;
+        }
+    }
+    table
+}
+
+/// For a sum of `SUITBIT_BY_ID` over up to seven cards, the flush suit
+/// plus one, or 0 without a flush
+pub fn suits() -> Vec<usize> {
+    let mut table = vec![0; 1 << 12];
+    for (hash, suit) in table.iter_mut().enumerate() {
+        let counts: Vec<usize> = (0..4).map(|s| hash >> (3 * s) & 0x7).collect();
+        if counts.iter().sum::<usize>() > 7 {
+            continue;
+        }
+        if let Some(s) = counts.iter().position(|&n| n >= 5) {
+            *suit = s + 1;
+        }
+    }
+    table
+}
+
+/// Rank of every five card quinary, indexed by its quinary hash
+pub fn noflush5() -> Vec<usize> {
+    let values = hand_values();
+    let dp = dp();
+    let mut table = vec![0; 6175];
+    quinaries(&mut [0; 13], 0, 5, &mut |q| {
+        table[hash(&dp, q, 5)] = rank_of(&values, &hand_value(q, false));
+    });
+    table
+}
+
+/// `hash_quinary` over a table made by `dp`, deuces the most significant
+fn hash(dp: &[[[usize; 8]; 14]; 5], q: &[usize; 13], k: usize) -> usize {
+    let mut sum = 0;
+    let mut k = k;
+    for i in 0..13 {
+        if k == 0 {
+            break;
+        }
+        sum += dp[q[i]][13 - i - 1][k];
+        k -= q[i];
+    }
+    sum
+}
+
+mod tests {
+    #[test]
+    fn test_hand_values() {
+        use tables::{hand_values, Category};
+
+        let values = hand_values();
+        assert_eq!(7462, values.len());
+        let count = |c| values.iter().filter(|v| v.category == c).count();
+        assert_eq!(10, count(Category::StraightFlush));
+        assert_eq!(156, count(Category::Quads));
+        assert_eq!(1277, count(Category::Flush));
+        assert_eq!(2860, count(Category::Pair));
+        // the wheel is the lowest straight
+        assert_eq!(vec![3], values[1609 - 1].ranks);
+    }
+}
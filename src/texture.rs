@@ -0,0 +1,319 @@
+//! Board texture: how suited, paired and connected a flop, turn or river is.
+
+use std::fmt::{self, Display};
+
+use card::{Card, CardRepr, Flop, Rank};
+use iso;
+
+/// Most cards the board has in one suit
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Suits {
+    /// No two cards share a suit
+    Rainbow,
+    /// Two of a suit, a flush draw
+    TwoTone,
+    /// Three or more of a suit and other suits too, e.g. 2s5s9sKd
+    ThreeFlush,
+    /// Every card of one suit
+    Monotone,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Pairing {
+    Unpaired,
+    Paired,
+    TwoPair,
+    Trips,
+    FullHouse,
+    Quads,
+}
+
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Texture {
+    pub suits: Suits,
+    pub pairing: Pairing,
+    pub high: Rank,
+    /// Most distinct ranks inside any five rank window, aces playing high
+    /// and low. Three means two hole cards can make a straight.
+    pub connected: u32,
+}
+
+/// One thing a texture can be, named as in `Display`, e.g. "monotone",
+/// "paired" or "K high"
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub enum Label {
+    Suits(Suits),
+    Pairing(Pairing),
+    Connected,
+    High(Rank),
+    Wet,
+    Dry,
+}
+
+/// What one more card did to the board
+#[derive(PartialEq, Eq, Debug, Copy, Clone)]
+pub struct Change {
+    pub flush: bool,
+    /// More cards in a straight window than before, and at least three
+    pub straight: bool,
+    /// Cards in the best straight window after the card
+    pub connected: u32,
+    pub paired: bool,
+    pub overcard: bool,
+}
+
+/// Board cards as one 13 bit rank set per suit, clubs first, from `to_52`
+fn suit_masks(board: &[Card]) -> [u64; 4] {
+    let mask = board.iter().fold(0, |m, c| m | c.to_52());
+    let mut ret = [0; 4];
+    for (suit, m) in ret.iter_mut().enumerate() {
+        *m = mask >> ((3 - suit) * 13) & 0x1fff;
+    }
+    ret
+}
+
+/// Bit 1 + value for each rank on the board, bit 0 for a low ace
+fn rank_bits(masks: &[u64; 4]) -> u64 {
+    // `to_52` puts aces on bit 0 and deuces on bit 12
+    let ranks = masks.iter().fold(0, |m, s| m | s);
+    let mut ret = 0;
+    for v in 0..13 {
+        if ranks & 1 << (12 - v) != 0 {
+            ret |= 1 << (v + 1);
+        }
+    }
+    ret | ret >> 13
+}
+
+fn connected(bits: u64) -> u32 {
+    (0..10).map(|lo| (bits >> lo & 0x1f).count_ones()).max().unwrap_or(0)
+}
+
+impl Texture {
+    pub fn of(board: &[Card]) -> Self {
+        let masks = suit_masks(board);
+        let most = masks.iter().map(|m| m.count_ones()).max().unwrap_or(0);
+        let suits = match most {
+            0 | 1 => Suits::Rainbow,
+            2 => Suits::TwoTone,
+            n if n as usize == board.len() => Suits::Monotone,
+            _ => Suits::ThreeFlush,
+        };
+
+        let mut counts = [0; 13];
+        for c in board {
+            counts[c.1.value() as usize] += 1;
+        }
+        let pairs = counts.iter().filter(|&&n| n == 2).count();
+        let pairing = match counts.iter().max() {
+            Some(4) => Pairing::Quads,
+            Some(3) if pairs > 0 => Pairing::FullHouse,
+            Some(3) => Pairing::Trips,
+            Some(2) if pairs > 1 => Pairing::TwoPair,
+            Some(2) => Pairing::Paired,
+            _ => Pairing::Unpaired,
+        };
+
+        let high = board.iter().map(|c| c.1).max_by_key(|r| r.value()).unwrap_or(Rank::Deuce);
+        Self {
+            suits,
+            pairing,
+            high,
+            connected: connected(rank_bits(&masks)),
+        }
+    }
+
+    pub fn flush_possible(&self) -> bool {
+        self.suits == Suits::ThreeFlush || self.suits == Suits::Monotone
+    }
+
+    pub fn straight_possible(&self) -> bool {
+        self.connected >= 3
+    }
+
+    /// Wet boards give draws something to hit: any two of a suit or three
+    /// cards close enough for a straight
+    pub fn is_wet(&self) -> bool {
+        self.suits != Suits::Rainbow || self.straight_possible()
+    }
+
+    /// Whether the texture answers to a name used in `Display`, e.g.
+    /// "monotone", "paired" or "dry"
+    pub fn is(&self, label: &str) -> bool {
+        Label::parse(label).is_some_and(|l| self.has(l))
+    }
+
+    pub fn has(&self, label: Label) -> bool {
+        match label {
+            Label::Suits(suits) => self.suits == suits,
+            Label::Pairing(pairing) => self.pairing == pairing,
+            Label::Connected => self.straight_possible(),
+            Label::High(rank) => self.high == rank,
+            Label::Wet => self.is_wet(),
+            Label::Dry => !self.is_wet(),
+        }
+    }
+
+    /// The labels the texture has, in `Display` order
+    fn labels(&self) -> Vec<Label> {
+        let mut ret = vec![Label::Suits(self.suits), Label::Pairing(self.pairing)];
+        if self.straight_possible() {
+            ret.push(Label::Connected);
+        }
+        ret.push(Label::High(self.high));
+        ret.push(if self.is_wet() { Label::Wet } else { Label::Dry });
+        ret
+    }
+}
+
+impl Label {
+    /// Every label but the "<rank> high" ones
+    const FIXED: [Label; 13] = [
+        Label::Suits(Suits::Rainbow), Label::Suits(Suits::TwoTone),
+        Label::Suits(Suits::ThreeFlush), Label::Suits(Suits::Monotone),
+        Label::Pairing(Pairing::Unpaired), Label::Pairing(Pairing::Paired),
+        Label::Pairing(Pairing::TwoPair), Label::Pairing(Pairing::Trips),
+        Label::Pairing(Pairing::FullHouse), Label::Pairing(Pairing::Quads),
+        Label::Connected, Label::Wet, Label::Dry,
+    ];
+
+    /// The label with this name, ignoring case
+    pub fn parse(name: &str) -> Option<Label> {
+        let name = name.trim();
+        let high = (0..13).map(|v| Label::High(Rank::get(v)));
+        Label::FIXED.iter().cloned().chain(high).find(|l| l.to_string().eq_ignore_ascii_case(name))
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        let name = match *self {
+            Label::Suits(Suits::Rainbow) => "rainbow",
+            Label::Suits(Suits::TwoTone) => "two-tone",
+            Label::Suits(Suits::ThreeFlush) => "three-flush",
+            Label::Suits(Suits::Monotone) => "monotone",
+            Label::Pairing(Pairing::Unpaired) => "unpaired",
+            Label::Pairing(Pairing::Paired) => "paired",
+            Label::Pairing(Pairing::TwoPair) => "two pair",
+            Label::Pairing(Pairing::Trips) => "trips",
+            Label::Pairing(Pairing::FullHouse) => "full house",
+            Label::Pairing(Pairing::Quads) => "quads",
+            Label::Connected => "connected",
+            Label::High(rank) => return write!(fmt, "{} high", rank),
+            Label::Wet => "wet",
+            Label::Dry => "dry",
+        };
+        write!(fmt, "{}", name)
+    }
+}
+
+/// Whether some board can answer to `label` in `Texture::is`
+pub fn is_label(label: &str) -> bool {
+    Label::parse(label).is_some()
+}
+
+/// Whether a flop dealt from `lowest` up to aces has every texture in
+/// `labels` and passes `keep`. Only one flop of each suit isomorphism class
+/// is tried: textures don't depend on which suit is which, and `keep` must
+/// not either.
+pub fn any_flop<F>(labels: &[String], lowest: Rank, keep: F) -> bool
+    where F: Fn(&[Card]) -> bool
+{
+    let labels: Option<Vec<Label>> = labels.iter().map(|l| Label::parse(l)).collect();
+    let labels = match labels {
+        Some(labels) => labels,
+        None => return false,
+    };
+    iso::flops().iter().any(|&(Flop(flop), _)| {
+        let texture = Texture::of(&flop);
+        flop.iter().all(|c| c.1.value() >= lowest.value()) && labels.iter().all(|&l| texture.has(l)) && keep(&flop)
+    })
+}
+
+impl Display for Texture {
+    fn fmt(&self, fmt: &mut ::std::fmt::Formatter<'_>) -> ::std::result::Result<(), fmt::Error> {
+        let labels: Vec<String> = self.labels().iter().map(|l| l.to_string()).collect();
+        write!(fmt, "{}", labels.join(", "))
+    }
+}
+
+impl Change {
+    /// What `card` adds to `board`
+    pub fn of(board: &[Card], card: Card) -> Self {
+        let before = Texture::of(board);
+        let mut next = board.to_vec();
+        next.push(card);
+        let after = Texture::of(&next);
+        Self {
+            flush: after.flush_possible() && !before.flush_possible(),
+            straight: after.straight_possible() && after.connected > before.connected,
+            connected: after.connected,
+            paired: board.iter().any(|c| c.1 == card.1),
+            overcard: card.1.value() > before.high.value(),
+        }
+    }
+
+    pub fn describe(&self) -> Vec<&'static str> {
+        let mut ret = Vec::new();
+        if self.flush {
+            ret.push("brings a third card of a suit, a flush is possible");
+        }
+        if self.straight {
+            ret.push(match self.connected {
+                3 => "puts three cards in a straight window",
+                4 => "puts four cards in a straight window, one card makes a straight",
+                _ => "puts a straight on the board",
+            });
+        }
+        if self.paired {
+            ret.push("pairs the board, full houses are possible");
+        }
+        if self.overcard {
+            ret.push("is an overcard to the board");
+        }
+        ret
+    }
+}
+
+mod tests {
+    #[test]
+    fn test_texture() {
+        use card::{parse_cards, Rank};
+        use texture::{any_flop, is_label, Change, Pairing, Suits, Texture};
+
+        let texture = |s: &str| Texture::of(&parse_cards(s).unwrap());
+        let dry = texture("Kc7d2h");
+        assert_eq!(Suits::Rainbow, dry.suits);
+        assert_eq!(Rank::King, dry.high);
+        assert!(!dry.is_wet());
+        assert_eq!("rainbow, unpaired, K high, dry", format!("{}", dry));
+
+        let wet = texture("JhTh9c");
+        assert_eq!(Suits::TwoTone, wet.suits);
+        assert_eq!(3, wet.connected);
+        assert!(wet.is("wet") && wet.is("Connected"));
+        assert_eq!(3, texture("Ah2c3d").connected);
+        assert_eq!(Pairing::FullHouse, texture("7c7d7h2c2d").pairing);
+        assert_eq!(Suits::Monotone, texture("2s5s9s").suits);
+        assert_eq!(Suits::ThreeFlush, texture("2s5s9sKd").suits);
+        assert!(texture("2s5s9sKd").is("three-flush") && !texture("2s5s9sKd").is("monotone"));
+        assert!(texture("2s5s9sKs").is("monotone"));
+
+        assert!(is_label("Two Pair") && is_label("T high") && !is_label("suited"));
+        let labels = |l: &[&str]| l.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+        assert!(!any_flop(&labels(&["three-flush"]), Rank::Deuce, |_| true));
+        assert!(any_flop(&labels(&["monotone", "connected"]), Rank::Deuce, |_| true));
+        assert!(!any_flop(&labels(&["monotone", "paired"]), Rank::Deuce, |_| true));
+        assert!(!any_flop(&labels(&["monotone", "dry"]), Rank::Deuce, |_| true));
+        assert!(!any_flop(&labels(&["5 high"]), Rank::Six, |_| true));
+
+        let flop = parse_cards("Kh7h2c").unwrap();
+        let change = Change::of(&flop, "Ah".parse().unwrap());
+        assert!(change.flush && change.overcard && !change.straight && !change.paired);
+        assert!(Change::of(&flop, "7c".parse().unwrap()).paired);
+        // 9-8-6 already allows a straight, the ten makes four in a window
+        let change = Change::of(&parse_cards("9h8c6d").unwrap(), "Ts".parse().unwrap());
+        assert!(change.straight && change.connected == 4);
+        assert!(!Change::of(&parse_cards("9h8c7d").unwrap(), "2s".parse().unwrap()).straight);
+    }
+}